@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MIT
+pragma solidity 0.8.25;
+
+interface IWalletCoreHash {
+    struct Call {
+        address target;
+        uint256 value;
+        bytes data;
+    }
+
+    function getValidationTypedHash(uint256 nonce, Call[] calldata calls) external view returns (bytes32);
+}
+
+// Validator cloned by WalletCore's addValidator for one session key, with the immutable args
+// abi.encode(sessionKey, token, maxAmount, validUntil, recipients).
+//
+// validate() accepts a batch signed by the session key before validUntil when every call but
+// the last transfers the token to an allowed recipient without sending ETH, and the last call is
+// record() of their total on this validator. validate() is a view, so the wallet counts what it
+// spent by running record() with the batch, which reverts above maxAmount.
+//
+// The validation data is abi.encode(calls, nonce, signature): the calls are checked against the
+// hash WalletCore passes, so they are the calls it executes.
+//
+// Assumes WalletCore clones validators with OpenZeppelin's Clones.cloneWithImmutableArgs, as its
+// CloneArgumentsTooLong error suggests, and passes getValidationTypedHash(nonce, calls) as msgHash.
+contract SessionKeyValidator {
+    error SessionCapExceeded(uint256 spent, uint256 maxAmount);
+
+    bytes4 private constant TRANSFER_SELECTOR = 0xa9059cbb;
+    bytes4 private constant ERC165_INTERFACE = 0x01ffc9a7;
+    uint256 private constant SECP256K1_HALF_ORDER =
+        0x7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0;
+
+    // Amount of the token each wallet has transferred through this validator
+    mapping(address wallet => uint256) public spent;
+
+    function validate(bytes32 msgHash, bytes calldata validationData) external view returns (bool) {
+        (IWalletCoreHash.Call[] memory calls, uint256 nonce, bytes memory signature) =
+            abi.decode(validationData, (IWalletCoreHash.Call[], uint256, bytes));
+        (address sessionKey, address token,, uint64 validUntil, address[] memory recipients) = _args();
+
+        if (block.timestamp >= validUntil || calls.length == 0) {
+            return false;
+        }
+        if (IWalletCoreHash(msg.sender).getValidationTypedHash(nonce, calls) != msgHash) {
+            return false;
+        }
+        if (_recover(msgHash, signature) != sessionKey) {
+            return false;
+        }
+
+        uint256 last = calls.length - 1;
+        uint256 total;
+        for (uint256 i; i < last; ++i) {
+            (bool allowed, uint256 amount) = _transfer(calls[i], token, recipients);
+            if (!allowed) {
+                return false;
+            }
+            total += amount;
+        }
+
+        IWalletCoreHash.Call memory record_ = calls[last];
+        return record_.target == address(this) && record_.value == 0
+            && keccak256(record_.data) == keccak256(abi.encodeCall(this.record, (total)));
+    }
+
+    // Count `amount` as spent by the calling wallet
+    function record(uint256 amount) external {
+        (,, uint256 maxAmount,,) = _args();
+        uint256 total = spent[msg.sender] + amount;
+        if (total > maxAmount) {
+            revert SessionCapExceeded(total, maxAmount);
+        }
+        spent[msg.sender] = total;
+    }
+
+    function supportsInterface(bytes4 interfaceId) external pure returns (bool) {
+        return interfaceId == this.validate.selector || interfaceId == ERC165_INTERFACE;
+    }
+
+    function _args()
+        private
+        view
+        returns (address sessionKey, address token, uint256 maxAmount, uint64 validUntil, address[] memory recipients)
+    {
+        // Clones.fetchCloneArgs: the args follow the 45 bytes of the clone's code
+        bytes memory args = new bytes(address(this).code.length - 45);
+        assembly ("memory-safe") {
+            extcodecopy(address(), add(args, 32), 45, mload(args))
+        }
+        return abi.decode(args, (address, address, uint256, uint64, address[]));
+    }
+
+    // Whether `call` transfers `token` to one of `recipients` without ETH, and its amount
+    function _transfer(IWalletCoreHash.Call memory call, address token, address[] memory recipients)
+        private
+        pure
+        returns (bool, uint256)
+    {
+        bytes memory data = call.data;
+        if (call.target != token || call.value != 0 || data.length != 68 || bytes4(data) != TRANSFER_SELECTOR) {
+            return (false, 0);
+        }
+
+        uint256 to;
+        uint256 amount;
+        assembly ("memory-safe") {
+            to := mload(add(data, 36))
+            amount := mload(add(data, 68))
+        }
+        if (to >> 160 != 0) {
+            return (false, 0);
+        }
+        for (uint256 i; i < recipients.length; ++i) {
+            if (recipients[i] == address(uint160(to))) {
+                return (true, amount);
+            }
+        }
+        return (false, 0);
+    }
+
+    function _recover(bytes32 hash, bytes memory signature) private pure returns (address) {
+        if (signature.length != 65) {
+            return address(0);
+        }
+
+        bytes32 r;
+        bytes32 s;
+        uint8 v;
+        assembly ("memory-safe") {
+            r := mload(add(signature, 32))
+            s := mload(add(signature, 64))
+            v := byte(0, mload(add(signature, 96)))
+        }
+        if (uint256(s) > SECP256K1_HALF_ORDER) {
+            return address(0);
+        }
+        if (v < 27) {
+            v += 27;
+        }
+        return ecrecover(hash, v, r, s);
+    }
+}
@@ -0,0 +1,575 @@
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::{
+    fs::{OpenOptions, Permissions},
+    io::Write,
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    eips::eip7702::SignedAuthorization,
+    network::TransactionBuilder7702,
+    primitives::{Address, B256, Bytes, U256, utils::parse_units},
+    signers::local::PrivateKeySigner,
+    providers::Provider,
+};
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tx_7702::{
+    Config,
+    core::{
+        AttemptKind, AuditEntry, AuditEvent, Call, CallBuilder, Delegation, EcdsaValidatorArgs, Eip7702Builder, Error,
+        MockRemoteSigner, SessionKey, SessionPolicy, ValidatorArgs, Relayer, RelayerClient, WalletSigner,
+        payouts, registry,
+        types::AuthorizationChain,
+        verify::chain_agnostic_warning,
+    },
+    examples,
+};
+
+use crate::cli::{
+    Account, AccountArgs, Cli, Command, DemoFlow, SimulateArgs,
+    output::{
+        AuthorizeOutput, BalanceOutput, DelegationOutput, NonceOutput, PayoutOutput, PayoutRowOutput, RelayOutput,
+        SessionOutput, SimulateOutput, TransactionOutput, ValidatorsOutput, emit,
+    },
+};
+
+/// Delay between two status requests while waiting for a relayed intent
+const RELAY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Call read from a batch file: raw `data`, or a function `signature` with JSON `args`
+#[derive(Debug, Deserialize)]
+struct CallSpec {
+    target: Address,
+    #[serde(default)]
+    value: U256,
+    #[serde(default)]
+    data: Bytes,
+    signature: Option<String>,
+    #[serde(default)]
+    args: Vec<serde_json::Value>,
+}
+
+/// Session key stored by `session-create` and used by `session-transfer`
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionFile {
+    wallet: Address,
+    private_key: B256,
+    /// Validator clone restricting the key
+    validator: Address,
+    policy: SessionPolicy,
+}
+
+/// Run a parsed command line
+pub async fn run(cli: Cli) -> Result<()> {
+    let global = cli.global;
+    let json = global.json;
+
+    // Load configuration from the config file, network profile and environment
+    let mut config = Config::connect(global.config.as_deref(), global.network.as_deref()).await?;
+
+    if global.any_chain {
+        eprintln!("warning: authorizations will be signed with chain_id = 0 and are valid on every chain");
+        config.authorization_chain = AuthorizationChain::Any;
+    }
+    if global.allow_unknown_delegate {
+        eprintln!("warning: delegates missing from the WalletCore registry will be signed");
+        config.allow_unknown_delegate = true;
+    }
+    if let Some(validator) = global.validator {
+        config.validator_address = validator;
+    }
+
+    let builder = Eip7702Builder::new(config.clone()).with_audit_hook(report_attempt);
+
+    match cli.command {
+        Command::Authorize { delegate, accounts } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let authorization = match delegate {
+                Some(delegate) => {
+                    builder
+                        .create_delegation_authorization(authority, delegate, sponsor.address())
+                        .await?
+                }
+                None => builder.create_authorization(authority, sponsor.address()).await?,
+            };
+            warn_chain_agnostic([&authorization]);
+            let output = AuthorizeOutput {
+                authority: authority.address(),
+                sponsor: sponsor.address(),
+                authorization,
+            };
+            emit(&output, json);
+        }
+        Command::Init { accounts } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let authorization = builder.create_authorization(authority, sponsor.address()).await?;
+            warn_chain_agnostic([&authorization]);
+            let tx = builder.build_initialize_transaction(authority.address(), authorization)?;
+            let receipt = builder.send_transaction(tx, sponsor).await?;
+
+            let mut output = TransactionOutput::new(authority.address(), sponsor.address(), &receipt);
+            output.storage_address = Some(builder.get_storage_address(authority.address()).await?);
+            emit(&output, json);
+        }
+        Command::DelegationStatus { address } => {
+            let address = address.unwrap_or(config.bob_signer.address());
+            emit(&delegation_status(&builder, address).await?, json);
+        }
+        Command::Transfer { transfer, accounts } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let calls = vec![transfer_call(
+                &builder,
+                transfer.token.as_deref(),
+                transfer.to,
+                &transfer.amount,
+                transfer.raw,
+            )
+            .await?];
+            let tx = builder
+                .build_signed_execute_transaction(authority, sponsor.address(), &calls)
+                .await?;
+            warn_chain_agnostic(tx.authorization_list().into_iter().flatten());
+            let receipt = builder.send_transaction(tx, sponsor).await?;
+            emit(&TransactionOutput::new(authority.address(), sponsor.address(), &receipt), json);
+        }
+        Command::Batch { file, accounts } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let calls = read_calls(&file)?;
+            let tx = builder
+                .build_signed_execute_transaction(authority, sponsor.address(), &calls)
+                .await?;
+            warn_chain_agnostic(tx.authorization_list().into_iter().flatten());
+            let receipt = builder.send_transaction(tx, sponsor).await?;
+            emit(&TransactionOutput::new(authority.address(), sponsor.address(), &receipt), json);
+        }
+        Command::Payout {
+            file,
+            max_gas,
+            accounts,
+        } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let rows = payouts::read_manifest(&file)?;
+            let transfers = builder.resolve_transfers(&rows).await?;
+            let reports = builder.send_transfers(authority, sponsor, &transfers, max_gas).await?;
+
+            let output = PayoutOutput {
+                rows: reports.iter().map(PayoutRowOutput::from).collect(),
+            };
+            emit(&output, json);
+        }
+        Command::Nonce { address } => {
+            let address = address.unwrap_or(config.bob_signer.address());
+            emit(&nonces(&builder, address).await?, json);
+        }
+        Command::Simulate { calls, accounts } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let calls = simulated_calls(&builder, calls).await?;
+            let tx = builder
+                .build_signed_execute_transaction(authority, sponsor.address(), &calls)
+                .await?;
+            warn_chain_agnostic(tx.authorization_list().into_iter().flatten());
+            let result = builder.simulate(&tx, sponsor.address()).await?;
+            emit(&SimulateOutput::from(&result), json);
+        }
+        Command::Revoke { accounts } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let receipt = builder.revoke_delegation(authority, sponsor).await?;
+            emit(&TransactionOutput::new(authority.address(), sponsor.address(), &receipt), json);
+        }
+        Command::Redelegate { delegate, accounts } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let receipt = builder.redelegate(authority, delegate, sponsor).await?;
+            emit(&TransactionOutput::new(authority.address(), sponsor.address(), &receipt), json);
+        }
+        Command::SpeedUp { tx_hash, from } => {
+            let signer = from.signer(&config);
+            let receipt = builder.speed_up_transaction(tx_hash, signer).await?;
+            emit(&TransactionOutput::new(signer.address(), signer.address(), &receipt), json);
+        }
+        Command::Cancel { tx_hash, from } => {
+            let signer = from.signer(&config);
+            let receipt = builder.cancel_transaction(tx_hash, signer).await?;
+            if receipt.transaction_hash == tx_hash {
+                eprintln!("warning: the original transaction was mined before its cancellation");
+            }
+            emit(&TransactionOutput::new(signer.address(), signer.address(), &receipt), json);
+        }
+        Command::AddValidator {
+            implementation,
+            args,
+            ecdsa_signer,
+            accounts,
+        } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let args = match ecdsa_signer {
+                Some(signer) => EcdsaValidatorArgs { signer }.immutable_args(),
+                None => args.unwrap_or_default(),
+            };
+            let (receipt, validator) = builder
+                .add_validator(authority, implementation, &args, sponsor)
+                .await?;
+
+            let mut output = TransactionOutput::new(authority.address(), sponsor.address(), &receipt);
+            output.validator_address = Some(validator);
+            emit(&output, json);
+        }
+        Command::Validators { address, from_block } => {
+            let address = address.unwrap_or(config.bob_signer.address());
+            let validators = builder.list_validators(address, from_block).await?;
+            emit(&ValidatorsOutput { address, validators }, json);
+        }
+        Command::SessionCreate {
+            implementation,
+            token,
+            max_amount,
+            raw,
+            recipients,
+            ttl,
+            out,
+            accounts,
+        } => {
+            let (authority, sponsor) = accounts.resolve(&config);
+            let token = resolve_token(&config, token.as_deref())?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let policy = SessionPolicy {
+                token,
+                max_amount: parse_amount(&builder, token, &max_amount, raw).await?,
+                recipients,
+                valid_until: now + ttl,
+            };
+
+            let (receipt, session) = builder
+                .create_session(authority, implementation, policy, sponsor)
+                .await?;
+            write_session(
+                &out,
+                &SessionFile {
+                    wallet: session.wallet(),
+                    private_key: session.signer().to_bytes(),
+                    validator: session.validator(),
+                    policy: session.policy().clone(),
+                },
+            )?;
+
+            let output = SessionOutput {
+                wallet: session.wallet(),
+                session_key: session.address(),
+                validator: session.validator(),
+                valid_until: session.policy().valid_until,
+                max_amount: session.policy().max_amount,
+                tx_hash: receipt.transaction_hash,
+                file: out,
+            };
+            emit(&output, json);
+        }
+        Command::SessionTransfer {
+            session: path,
+            sponsor,
+            transfer,
+        } => {
+            let file = read_session(&path)?;
+            let signer = PrivateKeySigner::from_bytes(&file.private_key).wrap_err("invalid session key")?;
+            let session = SessionKey::new(signer, file.wallet, file.validator, file.policy);
+
+            let calls = vec![transfer_call(
+                &builder,
+                transfer.token.as_deref(),
+                transfer.to,
+                &transfer.amount,
+                transfer.raw,
+            )
+            .await?];
+            let (receipt, sender) = match sponsor {
+                Some(sponsor) => {
+                    let sponsor = sponsor.signer(&config);
+                    let receipt = builder.send_session_transaction(&session, &calls, sponsor).await?;
+                    (receipt, sponsor.address())
+                }
+                None => {
+                    let receipt = builder
+                        .send_session_transaction(&session, &calls, session.signer())
+                        .await?;
+                    (receipt, session.address())
+                }
+            };
+            emit(&TransactionOutput::new(session.wallet(), sender, &receipt), json);
+        }
+        Command::Balance { address, token } => {
+            let address = address.unwrap_or(config.bob_signer.address());
+            let token = resolve_token(&config, token.as_deref())?;
+            let output = BalanceOutput {
+                address,
+                eth: builder.get_eth_balance(address).await?,
+                token,
+                token_balance: builder.get_balance_of(token, address).await?,
+            };
+            emit(&output, json);
+        }
+        Command::Relayer {
+            address,
+            signer,
+            queue_capacity,
+        } => {
+            let relayer = Arc::new(Relayer::new(builder, signer.signer(&config).clone(), queue_capacity));
+            let listener = TcpListener::bind(address).await?;
+            eprintln!(
+                "Relayer {} listening on http://{} (Ctrl-C to stop)",
+                relayer.address(),
+                listener.local_addr()?
+            );
+            tokio::select! {
+                result = relayer.serve(listener) => result?,
+                result = tokio::signal::ctrl_c() => result?,
+            }
+        }
+        Command::Relay {
+            url,
+            transfer,
+            from,
+            wait,
+        } => {
+            let client = RelayerClient::new(url);
+            let info = client.info().await?;
+            let call = transfer_call(&builder, transfer.token.as_deref(), transfer.to, &transfer.amount, transfer.raw).await?;
+            let intent = builder.sign_intent(from.signer(&config), info.address, &[call]).await?;
+
+            let id = client.submit(&intent).await?;
+            let mut status = client.status(id).await?;
+            while wait && !status.is_final() {
+                tokio::time::sleep(RELAY_POLL_INTERVAL).await;
+                status = client.status(id).await?;
+            }
+            emit(&RelayOutput { id, status }, json);
+        }
+        Command::RelayStatus { url, id } => {
+            let status = RelayerClient::new(url).status(id).await?;
+            emit(&RelayOutput { id, status }, json);
+        }
+        Command::MockSigner { address } => {
+            let keys = [&config.alice_signer, &config.bob_signer]
+                .into_iter()
+                .filter_map(|signer| match signer {
+                    WalletSigner::Local(key) => Some(key.clone()),
+                    WalletSigner::Remote(_) => None,
+                })
+                .collect();
+            let mock = MockRemoteSigner::spawn(address, keys).await?;
+            eprintln!("Mock remote signer listening on {} (Ctrl-C to stop)", mock.url());
+            tokio::signal::ctrl_c().await?;
+        }
+        Command::Demo { flow } => run_demo(config, flow).await?,
+    }
+
+    Ok(())
+}
+
+impl AccountArgs {
+    /// Get the signers of the acting account and of the account paying gas
+    fn resolve<'a>(&self, config: &'a Config) -> (&'a WalletSigner, &'a WalletSigner) {
+        let authority = self.from.signer(config);
+        let sponsor = self.sponsor.map_or(authority, |sponsor| sponsor.signer(config));
+        (authority, sponsor)
+    }
+}
+
+impl Account {
+    /// Get the configured signer of the account
+    fn signer(self, config: &Config) -> &WalletSigner {
+        match self {
+            Account::Alice => &config.alice_signer,
+            Account::Bob => &config.bob_signer,
+        }
+    }
+}
+
+/// Resolve `--token`, defaulting to the configured token
+fn resolve_token(config: &Config, token: Option<&str>) -> Result<Address> {
+    match token {
+        Some(token) => Ok(config.token(token)?),
+        None => Ok(config.token_address),
+    }
+}
+
+/// Build an ERC20 transfer call, scaling `amount` by the token's decimals unless `raw`
+async fn transfer_call(
+    builder: &Eip7702Builder,
+    token: Option<&str>,
+    to: Address,
+    amount: &str,
+    raw: bool,
+) -> Result<Call> {
+    let token = resolve_token(builder.config(), token)?;
+    let amount = parse_amount(builder, token, amount, raw).await?;
+    Ok(CallBuilder::new().erc20_transfer(token, to, amount).build().remove(0))
+}
+
+/// Parse an amount of `token`, scaling it by the token's decimals unless `raw`
+async fn parse_amount(builder: &Eip7702Builder, token: Address, amount: &str, raw: bool) -> Result<U256> {
+    let invalid = |reason: String| Error::InvalidConfig {
+        name: "amount",
+        reason: format!("`{}` {}", amount, reason),
+    };
+
+    if raw {
+        return Ok(amount.parse::<U256>().map_err(|e| invalid(e.to_string()))?);
+    }
+    let decimals = builder.get_token_decimals(token).await?;
+    let parsed = parse_units(amount, decimals).map_err(|e| invalid(e.to_string()))?;
+    if parsed.is_negative() {
+        return Err(invalid("is negative".to_string()).into());
+    }
+    Ok(parsed.get_absolute())
+}
+
+/// Read a session file
+fn read_session(path: &Path) -> Result<SessionFile> {
+    let contents = std::fs::read_to_string(path).wrap_err_with(|| format!("cannot read {}", path.display()))?;
+    serde_json::from_str(&contents).wrap_err_with(|| format!("invalid session file {}", path.display()))
+}
+
+/// Write a session file; it holds the session's private key, so only the owner may read it
+fn write_session(path: &Path, session: &SessionFile) -> Result<()> {
+    let contents = serde_json::to_string_pretty(session).expect("sessions serialize");
+    let cannot_write = || format!("cannot write {}", path.display());
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path).wrap_err_with(cannot_write)?;
+    // The mode only applies to new files
+    #[cfg(unix)]
+    file.set_permissions(Permissions::from_mode(0o600)).wrap_err_with(cannot_write)?;
+    file.write_all(contents.as_bytes()).wrap_err_with(cannot_write)
+}
+
+/// Read the calls of a batch file
+fn read_calls(path: &Path) -> Result<Vec<Call>> {
+    let contents = std::fs::read_to_string(path).wrap_err_with(|| format!("cannot read {}", path.display()))?;
+    let specs: Vec<CallSpec> =
+        serde_json::from_str(&contents).wrap_err_with(|| format!("invalid batch file {}", path.display()))?;
+
+    let mut calls = CallBuilder::new();
+    for spec in specs {
+        calls = match spec.signature {
+            Some(signature) => calls.function(spec.target, spec.value, &signature, &spec.args)?,
+            None => calls.raw(spec.target, spec.value, spec.data),
+        };
+    }
+    Ok(calls.build())
+}
+
+/// Print each transaction attempt as it is broadcast
+fn report_attempt(entry: &AuditEntry) {
+    if let AuditEvent::Submitted {
+        kind, tx_hash, nonce, ..
+    } = &entry.event
+    {
+        match kind {
+            AttemptKind::Original => eprintln!("Transaction submitted: {}", tx_hash),
+            AttemptKind::FeeBump => eprintln!("Replacement submitted: {} (nonce {})", tx_hash, nonce),
+            AttemptKind::Cancel => eprintln!("Cancellation submitted: {} (nonce {})", tx_hash, nonce),
+        }
+    }
+}
+
+/// Print a warning for each chain-agnostic authorization about to be used
+fn warn_chain_agnostic<'a>(authorizations: impl IntoIterator<Item = &'a SignedAuthorization>) {
+    for warning in authorizations.into_iter().filter_map(chain_agnostic_warning) {
+        eprintln!("warning: {}", warning);
+    }
+}
+
+/// Get the calls selected by the `simulate` arguments
+async fn simulated_calls(builder: &Eip7702Builder, args: SimulateArgs) -> Result<Vec<Call>> {
+    if let Some(file) = args.file {
+        return read_calls(&file);
+    }
+
+    // Both are required by the argument parser when no file is given
+    let to = args.to.expect("--to is required without --file");
+    let amount = args.amount.expect("--amount is required without --file");
+    Ok(vec![transfer_call(builder, args.token.as_deref(), to, &amount, args.raw).await?])
+}
+
+/// Describe the delegation of an address
+async fn delegation_status(builder: &Eip7702Builder, address: Address) -> Result<DelegationOutput> {
+    let chain_id = builder.chain_id().await?;
+    let delegation = builder.get_delegation(address).await?;
+
+    let (status, delegate) = match delegation {
+        Delegation::NotDelegated => ("not_delegated", None),
+        Delegation::WalletCore(delegate) => ("wallet_core", Some(delegate)),
+        Delegation::Other(delegate) => ("other", Some(delegate)),
+    };
+    let registry_version = delegate
+        .and_then(|delegate| registry::find(chain_id, delegate))
+        .map(|deployment| deployment.version);
+    let storage_address = match delegation {
+        Delegation::WalletCore(_) => builder.get_initialized_storage(address).await?,
+        _ => None,
+    };
+
+    Ok(DelegationOutput {
+        address,
+        status,
+        delegate,
+        registry_version,
+        storage_address,
+    })
+}
+
+/// Get the nonces of an address
+async fn nonces(builder: &Eip7702Builder, address: Address) -> Result<NonceOutput> {
+    let transaction_nonce = builder.provider().get_transaction_count(address).await?;
+    let wallet_nonce = match builder.get_delegation(address).await? {
+        Delegation::WalletCore(_) if builder.get_initialized_storage(address).await?.is_some() => {
+            Some(builder.get_wallet_nonce(address).await?)
+        }
+        _ => None,
+    };
+
+    Ok(NonceOutput {
+        address,
+        transaction_nonce,
+        self_authorization_nonce: builder.authorization_nonce(address, address).await?,
+        sponsored_authorization_nonce: transaction_nonce,
+        wallet_nonce,
+    })
+}
+
+/// Run one of the demo flows
+async fn run_demo(config: Config, flow: DemoFlow) -> Result<()> {
+    match flow {
+        DemoFlow::SelfAuthorization => {
+            println!("Running self-authorization pattern of 7702 erc20 transaction...");
+            examples::self_authorization_transaction(config).await
+        }
+        DemoFlow::Relayer => {
+            println!("Running relayer pattern of 7702 erc20 transaction...");
+            examples::relayer_transaction(config).await
+        }
+        DemoFlow::Simple => {
+            println!("Running simplest 7702 transaction");
+            examples::simple_transaction().await
+        }
+        DemoFlow::Normal => {
+            println!("Running normal ERC20 transfer without 7702");
+            examples::transfer_erc20(config).await
+        }
+        DemoFlow::Init => {
+            println!("Initializing wallet...");
+            examples::initialize_wallet(config.clone(), config.bob_signer).await
+        }
+        DemoFlow::InitBatch => {
+            println!("Initializing Alice's and Bob's wallets in one transaction...");
+            examples::initialize_wallets(config.clone(), config.bob_signer).await
+        }
+    }
+}
+
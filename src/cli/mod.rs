@@ -0,0 +1,404 @@
+mod commands;
+mod output;
+
+use std::{net::SocketAddr, path::PathBuf};
+
+use alloy::{
+    primitives::{Address, B256, Bytes, TxHash},
+    transports::http::reqwest::Url,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use tx_7702::core::queue::DEFAULT_QUEUE_CAPACITY;
+
+pub use commands::run;
+pub use output::{exit_code, report_error};
+
+/// EIP-7702 transactions through OKX WalletCore
+#[derive(Debug, Parser)]
+#[command(name = "tx-7702", version)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Options shared by every subcommand
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// Network profile to use
+    #[arg(long, global = true)]
+    pub network: Option<String>,
+
+    /// Config file with network profiles
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Print machine-readable JSON on stdout
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Sign authorizations with chain_id = 0, valid on every chain
+    #[arg(long, global = true, requires = "confirm_replay")]
+    pub any_chain: bool,
+
+    /// Confirm that chain-agnostic authorizations can be replayed on every chain
+    /// where WalletCore lives at the same address and the nonce matches
+    #[arg(long, global = true)]
+    pub confirm_replay: bool,
+
+    /// Sign authorizations for delegates missing from the WalletCore registry
+    #[arg(long, global = true)]
+    pub allow_unknown_delegate: bool,
+
+    /// Validator that executes batches; the network profile's if unset
+    #[arg(long, global = true)]
+    pub validator: Option<Address>,
+}
+
+/// Configured account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Account {
+    Alice,
+    Bob,
+}
+
+/// Who acts and who pays
+#[derive(Debug, Args)]
+pub struct AccountArgs {
+    /// Account whose EOA is delegated and acts
+    #[arg(long, value_enum, default_value_t = Account::Bob)]
+    pub from: Account,
+
+    /// Account that submits the transaction and pays gas; `--from` if unset
+    #[arg(long, value_enum)]
+    pub sponsor: Option<Account>,
+}
+
+/// ERC20 transfer
+#[derive(Debug, Args)]
+pub struct TransferArgs {
+    /// Token symbol from the network profile or token address; the default token if unset
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Recipient
+    #[arg(long)]
+    pub to: Address,
+
+    /// Amount in token units, e.g. `1.5`
+    #[arg(long)]
+    pub amount: String,
+
+    /// Read `--amount` in base units instead of token units
+    #[arg(long)]
+    pub raw: bool,
+}
+
+/// Calls to simulate: a batch file or a single transfer
+#[derive(Debug, Args)]
+pub struct SimulateArgs {
+    /// JSON file with the calls to execute
+    #[arg(long, conflicts_with_all = ["token", "to", "amount", "raw"])]
+    pub file: Option<PathBuf>,
+
+    /// Token symbol from the network profile or token address; the default token if unset
+    #[arg(long)]
+    pub token: Option<String>,
+
+    /// Recipient of the transfer
+    #[arg(long, required_unless_present = "file")]
+    pub to: Option<Address>,
+
+    /// Amount in token units, e.g. `1.5`
+    #[arg(long, required_unless_present = "file")]
+    pub amount: Option<String>,
+
+    /// Read `--amount` in base units instead of token units
+    #[arg(long)]
+    pub raw: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Sign an authorization without sending it
+    Authorize {
+        /// Contract to delegate to; the configured WalletCore if unset
+        #[arg(long)]
+        delegate: Option<Address>,
+
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// Delegate an EOA to WalletCore and create its storage
+    Init {
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// Show the delegation of an address
+    DelegationStatus {
+        /// Address to inspect; Bob if unset
+        address: Option<Address>,
+    },
+
+    /// Transfer ERC20 tokens from a WalletCore wallet
+    Transfer {
+        #[command(flatten)]
+        transfer: TransferArgs,
+
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// Execute a batch of calls read from a JSON file
+    ///
+    /// The file holds an array of `{ "target": "0x…", "value": "0", "data": "0x…" }` objects;
+    /// `data` can be replaced by a function `signature` and its JSON `args`.
+    Batch {
+        /// JSON file with the calls to execute
+        #[arg(long)]
+        file: PathBuf,
+
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// Pay ERC20 transfers listed in a CSV or JSON manifest, in gas-bounded batches
+    ///
+    /// CSV files have a `token,recipient,amount` header; JSON files hold an array of objects
+    /// with the same fields. Amounts are in token units.
+    Payout {
+        /// Manifest of transfers
+        #[arg(long)]
+        file: PathBuf,
+
+        /// Maximum gas of one batch transaction
+        #[arg(long, default_value_t = 5_000_000)]
+        max_gas: u64,
+
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// Show the transaction, authorization and WalletCore nonces of an address
+    Nonce {
+        /// Address to inspect; Bob if unset
+        address: Option<Address>,
+    },
+
+    /// Simulate a transfer or a batch without sending it
+    Simulate {
+        #[command(flatten)]
+        calls: SimulateArgs,
+
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// Clear the delegation of an EOA
+    Revoke {
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// Point an EOA at another delegate
+    Redelegate {
+        /// New delegate contract
+        delegate: Address,
+
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// Resend a pending transaction with the same nonce and higher fees
+    SpeedUp {
+        /// Hash of the pending transaction
+        tx_hash: TxHash,
+
+        /// Account that sent the transaction
+        #[arg(long, value_enum, default_value_t = Account::Bob)]
+        from: Account,
+    },
+
+    /// Replace a pending transaction with a zero-value self-send at higher fees
+    Cancel {
+        /// Hash of the pending transaction
+        tx_hash: TxHash,
+
+        /// Account that sent the transaction
+        #[arg(long, value_enum, default_value_t = Account::Bob)]
+        from: Account,
+    },
+
+    /// Add a validator to a delegated EOA, cloned from an implementation with immutable args
+    ///
+    /// WalletCore cannot remove a validator: once added, it stays usable for as long as the
+    /// EOA delegates to WalletCore.
+    AddValidator {
+        /// Validator implementation
+        implementation: Address,
+
+        /// Immutable args of the clone, hex encoded
+        #[arg(long, conflicts_with = "ecdsa_signer")]
+        args: Option<Bytes>,
+
+        /// Encode the args of an ECDSA validator accepting signatures from this key
+        #[arg(long)]
+        ecdsa_signer: Option<Address>,
+
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// List the validators added to a delegated EOA
+    ///
+    /// Every added validator is listed, since WalletCore cannot remove one.
+    Validators {
+        /// Address to inspect; Bob if unset
+        address: Option<Address>,
+
+        /// First block to read logs from; the block WalletCore was deployed in if unset
+        #[arg(long)]
+        from_block: Option<u64>,
+    },
+
+    /// Generate a session key that may transfer a token to allowed recipients until it
+    /// expires, and add a validator restricting it to that policy
+    ///
+    /// The validator is cloned from a deployed `contracts/SessionKeyValidator.sol`, which
+    /// enforces the token, recipients, amount and expiry on chain. The key is written to `--out`
+    /// in plain text, readable by its owner only.
+    SessionCreate {
+        /// Deployed `SessionKeyValidator` implementation
+        implementation: Address,
+
+        /// Token symbol from the network profile or token address; the default token if unset
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Total amount the session may transfer, in token units
+        #[arg(long)]
+        max_amount: String,
+
+        /// Read `--max-amount` in base units instead of token units
+        #[arg(long)]
+        raw: bool,
+
+        /// Recipient the session may transfer to; repeat for several
+        #[arg(long = "recipient", required = true)]
+        recipients: Vec<Address>,
+
+        /// Lifetime of the session in seconds
+        #[arg(long, default_value_t = 3600)]
+        ttl: u64,
+
+        /// File the session is written to
+        #[arg(long, default_value = "session.json")]
+        out: PathBuf,
+
+        #[command(flatten)]
+        accounts: AccountArgs,
+    },
+
+    /// Transfer tokens with a session key, after checking the session's policy against what
+    /// its validator has counted on chain
+    SessionTransfer {
+        /// Session file written by `session-create`
+        #[arg(long, default_value = "session.json")]
+        session: PathBuf,
+
+        /// Account that submits the transaction and pays gas; the session key if unset
+        #[arg(long, value_enum)]
+        sponsor: Option<Account>,
+
+        #[command(flatten)]
+        transfer: TransferArgs,
+    },
+
+    /// Show the ETH and token balances of an address
+    Balance {
+        /// Address to inspect; Bob if unset
+        address: Option<Address>,
+
+        /// Token symbol from the network profile or token address; the default token if unset
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Serve a relayer that submits signed intents and pays their gas
+    Relayer {
+        /// Address to listen on
+        #[arg(default_value = "127.0.0.1:8560")]
+        address: SocketAddr,
+
+        /// Account that submits the transactions and pays gas
+        #[arg(long, value_enum, default_value_t = Account::Bob)]
+        signer: Account,
+
+        /// Number of intents that may wait to be broadcast
+        #[arg(long, default_value_t = DEFAULT_QUEUE_CAPACITY)]
+        queue_capacity: usize,
+    },
+
+    /// Sign a transfer as an intent and send it to a relayer
+    Relay {
+        /// URL of the relayer
+        #[arg(long)]
+        url: Url,
+
+        #[command(flatten)]
+        transfer: TransferArgs,
+
+        /// Account whose EOA is delegated and acts
+        #[arg(long, value_enum, default_value_t = Account::Alice)]
+        from: Account,
+
+        /// Wait until the transaction is mined or fails
+        #[arg(long)]
+        wait: bool,
+    },
+
+    /// Show the status of an intent sent to a relayer
+    RelayStatus {
+        /// URL of the relayer
+        #[arg(long)]
+        url: Url,
+
+        /// Tracking id returned by the relayer
+        id: B256,
+    },
+
+    /// Serve the locally configured keys with the remote signer protocol
+    MockSigner {
+        /// Address to listen on
+        #[arg(default_value = "127.0.0.1:8550")]
+        address: SocketAddr,
+    },
+
+    /// Run one of the demo flows
+    Demo {
+        #[arg(value_enum)]
+        flow: DemoFlow,
+    },
+}
+
+/// Demo flows from the examples module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DemoFlow {
+    /// Bob transfers tokens from his own delegated EOA
+    #[value(name = "self")]
+    SelfAuthorization,
+    /// Alice signs off-chain and Bob submits and pays gas
+    Relayer,
+    /// Simplest EIP-7702 transaction on a local Anvil node
+    Simple,
+    /// Plain ERC20 transfer without EIP-7702
+    Normal,
+    /// Delegate and initialize Alice's wallet, paid by Bob
+    Init,
+    /// Delegate and initialize Alice and Bob in one transaction
+    InitBatch,
+}
@@ -0,0 +1,446 @@
+use std::{fmt, path::PathBuf};
+
+use alloy::{
+    eips::eip7702::SignedAuthorization,
+    primitives::{Address, B256, I256, TxHash, U256},
+    rpc::types::TransactionReceipt,
+};
+use serde::Serialize;
+use serde_json::json;
+use tx_7702::core::{
+    BalanceDelta, Error, ErrorKind, InstalledValidator, RelayStatus, SimulationMode, SimulationResult, TransferReport,
+    TransferStatus,
+};
+
+/// Print a command result as JSON or as text
+pub fn emit<T: Serialize + fmt::Display>(value: &T, json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value).expect("outputs serialize"));
+    } else {
+        println!("{}", value);
+    }
+}
+
+/// Exit code for an error: 1 for uncategorized errors, 2 is used by argument parsing
+pub fn exit_code(error: &eyre::Report) -> u8 {
+    match error.downcast_ref::<Error>().map(Error::kind) {
+        None => 1,
+        Some(ErrorKind::Config) => 3,
+        Some(ErrorKind::Network) => 4,
+        Some(ErrorKind::Signature) => 5,
+        Some(ErrorKind::Reverted) => 6,
+        Some(ErrorKind::Delegation) => 7,
+    }
+}
+
+/// Print an error as JSON on stdout or as text on stderr
+pub fn report_error(error: &eyre::Report, json: bool) {
+    if json {
+        let kind = match error.downcast_ref::<Error>().map(Error::kind) {
+            None => "other",
+            Some(ErrorKind::Config) => "config",
+            Some(ErrorKind::Network) => "network",
+            Some(ErrorKind::Signature) => "signature",
+            Some(ErrorKind::Reverted) => "reverted",
+            Some(ErrorKind::Delegation) => "delegation",
+        };
+        let output = json!({ "error": { "kind": kind, "message": format!("{:#}", error) } });
+        println!("{}", serde_json::to_string_pretty(&output).expect("errors serialize"));
+    } else {
+        eprintln!("error: {:#}", error);
+    }
+}
+
+/// Signed authorization that was not sent
+#[derive(Debug, Serialize)]
+pub struct AuthorizeOutput {
+    pub authority: Address,
+    pub sponsor: Address,
+    pub authorization: SignedAuthorization,
+}
+
+impl fmt::Display for AuthorizeOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let authorization = &self.authorization;
+        writeln!(f, "authority: {}", self.authority)?;
+        writeln!(f, "sponsor:   {}", self.sponsor)?;
+        writeln!(f, "chain id:  {}", authorization.chain_id)?;
+        writeln!(f, "delegate:  {}", authorization.address)?;
+        writeln!(f, "nonce:     {}", authorization.nonce)?;
+        writeln!(f, "y parity:  {}", authorization.y_parity())?;
+        writeln!(f, "r:         {:#x}", authorization.r())?;
+        write!(f, "s:         {:#x}", authorization.s())
+    }
+}
+
+/// Mined transaction
+#[derive(Debug, Serialize)]
+pub struct TransactionOutput {
+    pub from: Address,
+    pub sponsor: Address,
+    pub tx_hash: TxHash,
+    pub block_number: Option<u64>,
+    pub gas_used: u64,
+    /// Storage created by `initialize()`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_address: Option<Address>,
+    /// Validator added by the transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validator_address: Option<Address>,
+}
+
+impl TransactionOutput {
+    pub fn new(from: Address, sponsor: Address, receipt: &TransactionReceipt) -> Self {
+        TransactionOutput {
+            from,
+            sponsor,
+            tx_hash: receipt.transaction_hash,
+            block_number: receipt.block_number,
+            gas_used: receipt.gas_used,
+            storage_address: None,
+            validator_address: None,
+        }
+    }
+}
+
+impl fmt::Display for TransactionOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "from:      {}", self.from)?;
+        writeln!(f, "sponsor:   {}", self.sponsor)?;
+        writeln!(f, "tx hash:   {}", self.tx_hash)?;
+        match self.block_number {
+            Some(block_number) => writeln!(f, "block:     {}", block_number)?,
+            None => writeln!(f, "block:     pending")?,
+        }
+        write!(f, "gas used:  {}", self.gas_used)?;
+        if let Some(storage_address) = self.storage_address {
+            write!(f, "\nstorage:   {}", storage_address)?;
+        }
+        if let Some(validator_address) = self.validator_address {
+            write!(f, "\nvalidator: {}", validator_address)?;
+        }
+        Ok(())
+    }
+}
+
+/// Delegation of an address
+#[derive(Debug, Serialize)]
+pub struct DelegationOutput {
+    pub address: Address,
+    /// `not_delegated`, `wallet_core` or `other`
+    pub status: &'static str,
+    pub delegate: Option<Address>,
+    /// Version of the delegate in the WalletCore registry
+    pub registry_version: Option<&'static str>,
+    /// WalletCore storage, if the wallet is initialized
+    pub storage_address: Option<Address>,
+}
+
+impl fmt::Display for DelegationOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "address:   {}", self.address)?;
+        write!(f, "status:    {}", self.status)?;
+        if let Some(delegate) = self.delegate {
+            write!(f, "\ndelegate:  {}", delegate)?;
+        }
+        if let Some(version) = self.registry_version {
+            write!(f, "\nregistry:  WalletCore {}", version)?;
+        }
+        if let Some(storage_address) = self.storage_address {
+            write!(f, "\nstorage:   {}", storage_address)?;
+        }
+        Ok(())
+    }
+}
+
+/// Nonces of an address
+#[derive(Debug, Serialize)]
+pub struct NonceOutput {
+    pub address: Address,
+    pub transaction_nonce: u64,
+    /// Nonce of an authorization the address submits itself
+    pub self_authorization_nonce: u64,
+    /// Nonce of an authorization submitted by someone else
+    pub sponsored_authorization_nonce: u64,
+    /// Nonce of the WalletCore storage, if the wallet is initialized
+    pub wallet_nonce: Option<U256>,
+}
+
+impl fmt::Display for NonceOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "address:                   {}", self.address)?;
+        writeln!(f, "transaction nonce:         {}", self.transaction_nonce)?;
+        writeln!(f, "self authorization nonce:  {}", self.self_authorization_nonce)?;
+        write!(f, "sponsored authorization:   {}", self.sponsored_authorization_nonce)?;
+        match self.wallet_nonce {
+            Some(nonce) => write!(f, "\nwallet nonce:              {}", nonce),
+            None => write!(f, "\nwallet nonce:              not initialized"),
+        }
+    }
+}
+
+/// Token balance change in a simulation
+#[derive(Debug, Serialize)]
+pub struct BalanceDeltaOutput {
+    pub token: Address,
+    pub account: Address,
+    pub before: U256,
+    pub after: U256,
+    pub delta: I256,
+}
+
+impl From<&BalanceDelta> for BalanceDeltaOutput {
+    fn from(delta: &BalanceDelta) -> Self {
+        BalanceDeltaOutput {
+            token: delta.token,
+            account: delta.account,
+            before: delta.before,
+            after: delta.after,
+            delta: delta.delta(),
+        }
+    }
+}
+
+/// Simulated transaction
+#[derive(Debug, Serialize)]
+pub struct SimulateOutput {
+    pub success: bool,
+    /// `authorization_list` or `state_override`
+    pub mode: &'static str,
+    pub gas_used: u64,
+    pub gas_estimate: Option<u64>,
+    pub logs: usize,
+    pub balance_deltas: Vec<BalanceDeltaOutput>,
+    pub revert: Option<String>,
+}
+
+impl From<&SimulationResult> for SimulateOutput {
+    fn from(result: &SimulationResult) -> Self {
+        SimulateOutput {
+            success: result.success,
+            mode: match result.mode {
+                SimulationMode::AuthorizationList => "authorization_list",
+                SimulationMode::StateOverride => "state_override",
+            },
+            gas_used: result.gas_used,
+            gas_estimate: result.gas_estimate,
+            logs: result.logs.len(),
+            balance_deltas: result.balance_deltas.iter().map(BalanceDeltaOutput::from).collect(),
+            revert: result.revert.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+impl fmt::Display for SimulateOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "success:   {}", self.success)?;
+        writeln!(f, "mode:      {}", self.mode)?;
+        writeln!(f, "gas used:  {}", self.gas_used)?;
+        match self.gas_estimate {
+            Some(gas) => writeln!(f, "estimate:  {}", gas)?,
+            None => writeln!(f, "estimate:  -")?,
+        }
+        write!(f, "logs:      {}", self.logs)?;
+        for delta in &self.balance_deltas {
+            write!(
+                f,
+                "\n{} of {}: {} -> {} ({})",
+                delta.token, delta.account, delta.before, delta.after, delta.delta
+            )?;
+        }
+        if let Some(revert) = &self.revert {
+            write!(f, "\nrevert:    {}", revert)?;
+        }
+        Ok(())
+    }
+}
+
+/// Balances of an address
+#[derive(Debug, Serialize)]
+pub struct BalanceOutput {
+    pub address: Address,
+    /// ETH balance in wei
+    pub eth: U256,
+    pub token: Address,
+    /// Token balance in base units
+    pub token_balance: U256,
+}
+
+impl fmt::Display for BalanceOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "address:   {}", self.address)?;
+        writeln!(f, "eth (wei): {}", self.eth)?;
+        write!(f, "{}: {}", self.token, self.token_balance)
+    }
+}
+
+/// Outcome of one payout row
+#[derive(Debug, Serialize)]
+pub struct PayoutRowOutput {
+    pub row: usize,
+    pub token: Address,
+    pub recipient: Address,
+    /// Amount in base units
+    pub amount: U256,
+    /// `sent`, `failed` or `not_sent`
+    pub status: &'static str,
+    pub tx_hash: Option<TxHash>,
+    pub block_number: Option<u64>,
+    pub log_index: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl From<&TransferReport> for PayoutRowOutput {
+    fn from(report: &TransferReport) -> Self {
+        let transfer = &report.transfer;
+        let mut output = PayoutRowOutput {
+            row: transfer.row,
+            token: transfer.token,
+            recipient: transfer.recipient,
+            amount: transfer.amount,
+            status: "not_sent",
+            tx_hash: None,
+            block_number: None,
+            log_index: None,
+            error: None,
+        };
+        match &report.status {
+            TransferStatus::Sent {
+                tx_hash,
+                block_number,
+                log_index,
+            } => {
+                output.status = "sent";
+                output.tx_hash = Some(*tx_hash);
+                output.block_number = *block_number;
+                output.log_index = *log_index;
+            }
+            TransferStatus::Failed(error) => {
+                output.status = "failed";
+                output.error = Some(error.clone());
+            }
+            TransferStatus::NotSent => {}
+        }
+        output
+    }
+}
+
+/// Outcome of a payout
+#[derive(Debug, Serialize)]
+pub struct PayoutOutput {
+    pub rows: Vec<PayoutRowOutput>,
+}
+
+impl fmt::Display for PayoutOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let outcome = match (row.tx_hash, &row.error) {
+                    (Some(tx_hash), _) => match row.log_index {
+                        Some(log_index) => format!("{} log {}", tx_hash, log_index),
+                        None => format!("{} (no Transfer event)", tx_hash),
+                    },
+                    (None, Some(error)) => error.clone(),
+                    (None, None) => String::new(),
+                };
+                format!(
+                    "row {}: {} {} -> {}: {} {}",
+                    row.row, row.amount, row.token, row.recipient, row.status, outcome
+                )
+            })
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Intent sent to a relayer
+#[derive(Debug, Serialize)]
+pub struct RelayOutput {
+    pub id: B256,
+    #[serde(flatten)]
+    pub status: RelayStatus,
+}
+
+impl fmt::Display for RelayOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "id:        {}", self.id)?;
+        match &self.status {
+            RelayStatus::Pending => write!(f, "status:    pending"),
+            RelayStatus::Submitted { tx_hash } => {
+                writeln!(f, "status:    submitted")?;
+                write!(f, "tx hash:   {}", tx_hash)
+            }
+            RelayStatus::Mined {
+                tx_hash,
+                block_number,
+                gas_used,
+            } => {
+                writeln!(f, "status:    mined")?;
+                writeln!(f, "tx hash:   {}", tx_hash)?;
+                match block_number {
+                    Some(block_number) => writeln!(f, "block:     {}", block_number)?,
+                    None => writeln!(f, "block:     pending")?,
+                }
+                write!(f, "gas used:  {}", gas_used)
+            }
+            RelayStatus::Failed { tx_hash, error } => {
+                writeln!(f, "status:    failed")?;
+                if let Some(tx_hash) = tx_hash {
+                    writeln!(f, "tx hash:   {}", tx_hash)?;
+                }
+                write!(f, "error:     {}", error)
+            }
+        }
+    }
+}
+
+/// Validators added to a wallet
+#[derive(Debug, Serialize)]
+pub struct ValidatorsOutput {
+    pub address: Address,
+    pub validators: Vec<InstalledValidator>,
+}
+
+impl fmt::Display for ValidatorsOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "validators of {}:", self.address)?;
+        if self.validators.is_empty() {
+            write!(f, " none")?;
+        }
+        for validator in &self.validators {
+            write!(f, "\n  {}", validator.address)?;
+            if let Some(block_number) = validator.block_number {
+                write!(f, " (block {})", block_number)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Session key and the validator restricting it
+#[derive(Debug, Serialize)]
+pub struct SessionOutput {
+    pub wallet: Address,
+    pub session_key: Address,
+    pub validator: Address,
+    pub valid_until: u64,
+    pub max_amount: U256,
+    /// Transaction adding the validator
+    pub tx_hash: TxHash,
+    /// File holding the session's private key
+    pub file: PathBuf,
+}
+
+impl fmt::Display for SessionOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "wallet:      {}", self.wallet)?;
+        writeln!(f, "session key: {}", self.session_key)?;
+        writeln!(f, "validator:   {}", self.validator)?;
+        writeln!(f, "valid until: {}", self.valid_until)?;
+        writeln!(f, "max amount:  {}", self.max_amount)?;
+        writeln!(f, "tx hash:     {}", self.tx_hash)?;
+        write!(f, "saved to:    {}", self.file.display())
+    }
+}
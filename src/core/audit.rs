@@ -0,0 +1,158 @@
+use std::{
+    fmt,
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::{Address, TxHash};
+use serde::{Deserialize, Serialize};
+
+/// Why a transaction was sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptKind {
+    /// First transaction with its nonce
+    Original,
+    /// Same transaction with higher fees
+    FeeBump,
+    /// Zero-value self-send replacing a stuck transaction
+    Cancel,
+}
+
+/// Something that happened to a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// A transaction was accepted by the node
+    Submitted {
+        kind: AttemptKind,
+        from: Address,
+        nonce: u64,
+        tx_hash: TxHash,
+        max_fee_per_gas: Option<u128>,
+        max_priority_fee_per_gas: Option<u128>,
+        /// Transaction it replaces, for fee bumps and cancellations
+        replaces: Option<TxHash>,
+    },
+    /// The node refused a transaction
+    Rejected {
+        kind: AttemptKind,
+        from: Address,
+        nonce: u64,
+        max_fee_per_gas: Option<u128>,
+        max_priority_fee_per_gas: Option<u128>,
+        error: String,
+    },
+    /// None of the transactions with a nonce was mined within the confirmation timeout
+    TimedOut {
+        from: Address,
+        nonce: u64,
+        tx_hashes: Vec<TxHash>,
+    },
+    /// One of the transactions with a nonce was mined
+    Mined {
+        from: Address,
+        nonce: u64,
+        tx_hash: TxHash,
+        block_number: Option<u64>,
+        success: bool,
+    },
+}
+
+/// Audit event with the time it was recorded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+}
+
+/// Callback receiving every recorded entry, e.g. to report progress
+pub type AuditHook = Box<dyn Fn(&AuditEntry) + Send + Sync>;
+
+/// Record of every transaction attempt, appended to a JSON lines file and passed to a hook
+#[derive(Default)]
+pub struct AuditLog {
+    path: Option<PathBuf>,
+    hook: Option<AuditHook>,
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("path", &self.path)
+            .field("hook", &self.hook.is_some())
+            .finish()
+    }
+}
+
+impl AuditLog {
+    /// Create a log, written to `path` if set
+    pub fn new(path: Option<PathBuf>) -> Self {
+        AuditLog { path, hook: None }
+    }
+
+    /// Pass every recorded entry to `hook`, replacing any previous one
+    pub fn set_hook(&mut self, hook: impl Fn(&AuditEntry) + Send + Sync + 'static) {
+        self.hook = Some(Box::new(hook));
+    }
+
+    /// Record an event
+    ///
+    /// A failure to write the file is reported on stderr and never fails the transaction.
+    pub fn record(&self, event: AuditEvent) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        let entry = AuditEntry { timestamp, event };
+
+        if let Some(path) = &self.path {
+            let line = serde_json::to_string(&entry).expect("audit entries serialize");
+            let written = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", line));
+            if let Err(e) = written {
+                eprintln!("warning: cannot write audit log {}: {}", path.display(), e);
+            }
+        }
+
+        if let Some(hook) = &self.hook {
+            hook(&entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn passes_entries_to_the_hook_and_the_file() {
+        let path = std::env::temp_dir().join(format!("tx7702-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let mut log = AuditLog::new(Some(path.clone()));
+        let hook_seen = seen.clone();
+        log.set_hook(move |entry| hook_seen.lock().unwrap().push(entry.event.clone()));
+        let event = AuditEvent::TimedOut {
+            from: Address::ZERO,
+            nonce: 1,
+            tx_hashes: vec![TxHash::ZERO],
+        };
+        log.record(event.clone());
+
+        assert_eq!(seen.lock().unwrap().as_slice(), std::slice::from_ref(&event));
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entry: AuditEntry = serde_json::from_str(written.trim_end()).unwrap();
+        assert_eq!(entry.event, event);
+    }
+}
@@ -1,81 +1,226 @@
 use alloy::{
     eips::eip7702::{Authorization, SignedAuthorization},
-    network::{TransactionBuilder, TransactionBuilder7702},
-    primitives::{Address, Bytes, FixedBytes, U256},
-    providers::{Provider, ProviderBuilder},
+    network::{Ethereum, EthereumWallet, TransactionBuilder, TransactionBuilder7702, TxSigner},
+    primitives::{Address, Bytes, FixedBytes, PrimitiveSignature as Signature, U256},
+    providers::{DynProvider, PendingTransactionBuilder, Provider, ProviderBuilder},
     rpc::types::TransactionRequest,
-    signers::{local::PrivateKeySigner, SignerSync},
+    sol_types::SolCall,
 };
-use eyre::Result;
+use tokio::sync::OnceCell;
 
 use crate::core::{
+    audit::{AttemptKind, AuditEntry, AuditLog},
+    calls::CallBuilder,
     config::Config,
     contracts::{Call, IWalletCore, IStorage, ERC20},
-    types::Addresses,
+    error::{Error, Result},
+    registry, revert,
+    signer::PayloadSigner,
+    typed_data,
+    validators,
+    types::{parse_delegation_designator, AuthorizationChain, Delegation},
 };
 
 /// EIP-7702 transaction builder and utilities
-pub struct Eip7702Builder {
+///
+/// The builder holds a single provider that is reused for every RPC request.
+pub struct Eip7702Builder<P = DynProvider> {
     config: Config,
+    provider: P,
+    audit: AuditLog,
+    /// Chain id of the node, checked against the profile on first use
+    chain_id: OnceCell<u64>,
 }
 
 impl Eip7702Builder {
-    /// Create a new EIP-7702 builder with the given configuration
+    /// Create a new EIP-7702 builder connected to `config.rpc_url` over HTTP
     pub fn new(config: Config) -> Eip7702Builder {
-        Eip7702Builder { config }
+        let provider = ProviderBuilder::new()
+            .on_http(config.rpc_url.clone())
+            .erased();
+        Eip7702Builder::with_provider(config, provider)
+    }
+}
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Create a new EIP-7702 builder on top of an existing provider
+    ///
+    /// Any transport, layer or filler configured on the provider is kept.
+    pub fn with_provider(config: Config, provider: P) -> Eip7702Builder<P> {
+        let audit = AuditLog::new(config.audit_log.clone());
+        Eip7702Builder {
+            config,
+            provider,
+            audit,
+            chain_id: OnceCell::new(),
+        }
+    }
+
+    /// Get the configuration used by this builder
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Get the provider used by this builder
+    pub fn provider(&self) -> &P {
+        &self.provider
     }
 
-    /// Create an authorization for an EOA to use WalletCore code
-    pub async fn create_authorization(
+    /// Get the record of the transactions sent by this builder
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    /// Pass every transaction attempt and outcome recorded in the audit log to `hook`
+    pub fn with_audit_hook(mut self, hook: impl Fn(&AuditEntry) + Send + Sync + 'static) -> Self {
+        self.audit.set_hook(hook);
+        self
+    }
+
+    /// Create an authorization for an EOA to use WalletCore code.
+    ///
+    /// `sender` is the account that will submit the transaction carrying the
+    /// authorization; the nonce is chosen accordingly (see [`Self::authorization_nonce`]).
+    pub async fn create_authorization<A: PayloadSigner>(
         &self,
-        authorizer: &PrivateKeySigner,
+        authorizer: &A,
+        sender: Address,
     ) -> Result<alloy::eips::eip7702::SignedAuthorization> {
-        let provider = ProviderBuilder::new()
-            .wallet(authorizer.clone())
-            .on_http(self.config.rpc_url.clone());
-        let nonce = provider.get_transaction_count(authorizer.address()).await?;
-        let chain_id = U256::from(provider.get_chain_id().await?);
+        let nonce = self.authorization_nonce(authorizer.address(), sender).await?;
+        self.create_authorization_with_nonce(authorizer, nonce).await
+    }
+
+    /// Create an authorization signed with an explicit nonce (e.g. for pipelined transactions)
+    pub async fn create_authorization_with_nonce<A: PayloadSigner>(
+        &self,
+        authorizer: &A,
+        nonce: u64,
+    ) -> Result<alloy::eips::eip7702::SignedAuthorization> {
+        self.sign_authorization(authorizer, self.config.wallet_core_address, nonce)
+            .await
+    }
+
+    /// Create an authorization pointing an EOA at an arbitrary delegate.
+    ///
+    /// Delegating to [`Address::ZERO`] revokes the current delegation.
+    pub async fn create_delegation_authorization<A: PayloadSigner>(
+        &self,
+        authorizer: &A,
+        delegate: Address,
+        sender: Address,
+    ) -> Result<SignedAuthorization> {
+        let nonce = self.authorization_nonce(authorizer.address(), sender).await?;
+        self.sign_authorization(authorizer, delegate, nonce).await
+    }
+
+    /// Sign an authorization for `delegate`, bound to the chain selected by
+    /// [`Config::authorization_chain`]
+    ///
+    /// Chain-agnostic authorizations are signed without notice; callers surface the risk with
+    /// [`crate::core::verify::chain_agnostic_warning`].
+    ///
+    /// Delegates missing from the registry are refused unless [`Config::allow_unknown_delegate`] is set.
+    async fn sign_authorization<A: PayloadSigner>(
+        &self,
+        authorizer: &A,
+        delegate: Address,
+        nonce: u64,
+    ) -> Result<SignedAuthorization> {
+        let current_chain_id = self.chain_id().await?;
+        if !delegate.is_zero()
+            && !self.config.allow_unknown_delegate
+            && !registry::is_known_delegate(current_chain_id, delegate)
+        {
+            return Err(Error::UnknownDelegate {
+                chain_id: current_chain_id,
+                delegate,
+            });
+        }
+
+        let chain_id = match self.config.authorization_chain {
+            AuthorizationChain::Current => U256::from(current_chain_id),
+            AuthorizationChain::Any => U256::ZERO,
+        };
 
         let authorization = Authorization {
             chain_id,
-            address: self.config.wallet_core_address,
+            address: delegate,
             nonce,
         };
 
-        let signature = authorizer.sign_hash_sync(&authorization.signature_hash())?;
+        let signature = authorizer.sign_authorization(&authorization).await?;
         Ok(authorization.into_signed(signature))
     }
 
-    /// Get the storage address for a wallet (read-only, no signer needed)
+    /// Get the chain id of the node, failing if the network profile declares another one
+    pub async fn chain_id(&self) -> Result<u64> {
+        let chain_id = self
+            .chain_id
+            .get_or_try_init(|| async {
+                let actual = self.provider.get_chain_id().await?;
+                match self.config.chain_id {
+                    Some(expected) if expected != actual => Err(Error::ChainIdMismatch { expected, actual }),
+                    _ => Ok(actual),
+                }
+            })
+            .await?;
+        Ok(*chain_id)
+    }
+
+    /// Get the nonce an authorization from `authority` must carry when sent by `sender`.
+    ///
+    /// The sender's nonce is incremented before the authorization list is processed,
+    /// so a self-sponsored authorization has to use the next nonce.
+    pub async fn authorization_nonce(&self, authority: Address, sender: Address) -> Result<u64> {
+        let nonce = self.provider.get_transaction_count(authority).await?;
+
+        if authority == sender {
+            Ok(nonce + 1)
+        } else {
+            Ok(nonce)
+        }
+    }
+
+    /// Get the current delegation of an EOA by parsing its `0xef0100` designator
+    pub async fn get_delegation(&self, address: Address) -> Result<Delegation> {
+        let code = self.provider.get_code_at(address).await?;
+        if code.is_empty() {
+            return Ok(Delegation::NotDelegated);
+        }
+
+        match parse_delegation_designator(&code) {
+            Some(delegate) if delegate == self.config.wallet_core_address => Ok(Delegation::WalletCore(delegate)),
+            Some(delegate) => Ok(Delegation::Other(delegate)),
+            None => Err(Error::NotAnEoa(address)),
+        }
+    }
+
+    /// Get the storage address for a wallet
     pub async fn get_storage_address(&self, wallet_address: Address) -> Result<Address> {
-        let provider = ProviderBuilder::new()
-            .on_http(self.config.rpc_url.clone());
-        let wallet = IWalletCore::new(wallet_address, provider);
+        let wallet = IWalletCore::new(wallet_address, &self.provider);
         Ok(wallet.getMainStorage().call().await?._0)
     }
 
+    /// Get the storage of a wallet delegated to WalletCore, `None` until it is deployed by `initialize()`
+    pub async fn get_initialized_storage(&self, wallet_address: Address) -> Result<Option<Address>> {
+        let storage_address = self.get_storage_address(wallet_address).await?;
+        let code = self.provider.get_code_at(storage_address).await?;
+        Ok((!code.is_empty()).then_some(storage_address))
+    }
+
     /// Get the current nonce from wallet storage
     pub async fn get_wallet_nonce(&self, wallet_address: Address) -> Result<U256> {
         let storage_address = self.get_storage_address(wallet_address).await?;
-        let provider = ProviderBuilder::new()   
-            .on_http(self.config.rpc_url.clone());
-        let storage = IStorage::new(storage_address, provider);
+        let storage = IStorage::new(storage_address, &self.provider);
         Ok(storage.getNonce().call().await?._0)
     }
 
-    /// Create a token transfer call
-    pub fn create_token_transfer_call(&self, caller: &PrivateKeySigner, to: Address, amount: U256) -> Call {
-        let provider = ProviderBuilder::new().wallet(caller.clone())
-            .on_http(self.config.rpc_url.clone());
-        let erc20 = ERC20::new(self.config.token_address, provider);
-        let transfer_call = erc20.transfer(to, amount);
-        let calldata = transfer_call.calldata().to_owned();
-
-        Call {
-            target: self.config.token_address,
-            value: U256::from(0),
-            data: calldata,
-        }
+    /// Create a transfer call of the configured token
+    pub fn create_token_transfer_call(&self, to: Address, amount: U256) -> Call {
+        CallBuilder::new()
+            .erc20_transfer(self.config.token_address, to, amount)
+            .build()
+            .remove(0)
     }
 
     /// Generate validation hash for a set of calls
@@ -85,9 +230,7 @@ impl Eip7702Builder {
         nonce: U256,
         calls: &[Call],
     ) -> Result<FixedBytes<32>> {
-        let provider = ProviderBuilder::new()
-            .on_http(self.config.rpc_url.clone());
-        let wallet = IWalletCore::new(wallet_address, provider);
+        let wallet = IWalletCore::new(wallet_address, &self.provider);
         Ok(wallet
             .getValidationTypedHash(nonce, calls.to_vec())
             .call()
@@ -95,110 +238,391 @@ impl Eip7702Builder {
             ._0)
     }
 
-    /// Sign validation data
-    pub fn sign_validation_data(
+    /// Compute the validation hash locally, without any RPC call
+    pub fn compute_validation_hash(
+        &self,
+        chain_id: u64,
+        wallet_address: Address,
+        nonce: U256,
+        calls: &[Call],
+    ) -> FixedBytes<32> {
+        let domain = typed_data::wallet_core_domain(chain_id, wallet_address);
+        typed_data::validation_hash(&domain, nonce, calls)
+    }
+
+    /// Check that the on-chain validation hash matches the locally computed one
+    pub async fn verify_validation_hash(
         &self,
-        signer: &PrivateKeySigner,
+        wallet_address: Address,
+        nonce: U256,
+        calls: &[Call],
+    ) -> Result<FixedBytes<32>> {
+        let chain_id = self.chain_id().await?;
+
+        let local = self.compute_validation_hash(chain_id, wallet_address, nonce, calls);
+        let onchain = self.get_validation_hash(wallet_address, nonce, calls).await?;
+        if local != onchain {
+            return Err(Error::ValidationHashMismatch {
+                wallet: wallet_address,
+                local,
+                onchain,
+            });
+        }
+
+        Ok(local)
+    }
+
+    /// Sign the validation data of `calls` for `wallet_address` at `nonce`
+    ///
+    /// The signer receives the EIP-712 `Execute` struct and hashes it itself; the hash must
+    /// equal `validation_hash`, the one WalletCore computes on chain.
+    pub async fn sign_validation_data<S: PayloadSigner>(
+        &self,
+        signer: &S,
+        wallet_address: Address,
+        nonce: U256,
+        calls: &[Call],
         validation_hash: &FixedBytes<32>,
-    ) -> Bytes {
-        let signature = signer.sign_hash_sync(validation_hash).unwrap();
-        Bytes::from(signature.as_bytes())
+    ) -> Result<Bytes> {
+        let chain_id = self.chain_id().await?;
+        let domain = typed_data::wallet_core_domain(chain_id, wallet_address);
+        let local = typed_data::validation_hash(&domain, nonce, calls);
+        if local != *validation_hash {
+            return Err(Error::ValidationHashMismatch {
+                wallet: wallet_address,
+                local,
+                onchain: *validation_hash,
+            });
+        }
+
+        let typed_data = typed_data::validation_typed_data(&domain, nonce, calls);
+        let signature = signer.sign_eip712(&typed_data).await?;
+        Ok(Bytes::from(signature.as_bytes()))
     }
 
     /// Build initialize transaction
     pub fn build_initialize_transaction(
         &self,
-        caller: &PrivateKeySigner,
         wallet_address: Address,
         authorization: SignedAuthorization,
-    ) -> TransactionRequest {
-        let provider = ProviderBuilder::new().wallet(caller.clone()).on_http(self.config.rpc_url.clone());
-        let wallet = IWalletCore::new(wallet_address, provider);
-        
-        let init_call = wallet.initialize();
-        let calldata = init_call.calldata().to_owned();
-
-        TransactionRequest::default()
+    ) -> Result<TransactionRequest> {
+        ensure_authority(&authorization, wallet_address)?;
+        let calldata = IWalletCore::initializeCall {}.abi_encode();
+
+        Ok(TransactionRequest::default()
             .with_to(wallet_address)
             .with_authorization_list(vec![authorization])
-            .with_input(calldata)
+            .with_input(calldata))
     }
 
     /// Build an EIP-7702 transaction
-    pub fn build_execute_with_validator_transaction(
+    ///
+    /// The authorization is left out when `to` already delegates to WalletCore.
+    pub async fn build_execute_with_validator_transaction(
         &self,
-        caller: &PrivateKeySigner,
         to: Address,
         authorization: SignedAuthorization,
         calls: &[Call],
         validation_data: Bytes,
-    ) -> TransactionRequest {
-        let provider = ProviderBuilder::new()
-            .on_http(self.config.rpc_url.clone());
-        let wallet = IWalletCore::new(caller.address(), provider);
-        
-        let execute_call = wallet.executeWithValidator(
-            calls.to_vec(),
-            Addresses::ECDSA_VALIDATOR,
-            validation_data,
-        );
-        let calldata = execute_call.calldata().to_owned();
+    ) -> Result<TransactionRequest> {
+        ensure_authority(&authorization, to)?;
+        let calldata = validators::execute_with_validator_input(calls, self.config.validator_address, validation_data);
 
-        TransactionRequest::default()
+        let tx = TransactionRequest::default()
             .with_to(to)
-            .with_authorization_list(vec![authorization])
-            .with_input(calldata)
+            .with_input(calldata);
+
+        match self.get_delegation(to).await? {
+            Delegation::WalletCore(_) => Ok(tx),
+            _ => Ok(tx.with_authorization_list(vec![authorization])),
+        }
+    }
+
+    /// Sign `calls` for `authority`'s wallet and build the `executeWithValidator` transaction
+    /// that `sender` will submit
+    pub async fn build_signed_execute_transaction<A: PayloadSigner>(
+        &self,
+        authority: &A,
+        sender: Address,
+        calls: &[Call],
+    ) -> Result<TransactionRequest> {
+        let wallet = authority.address();
+        let authorization = self.create_authorization(authority, sender).await?;
+        let nonce = self.get_wallet_nonce(wallet).await?;
+        let validation_hash = self.get_validation_hash(wallet, nonce, calls).await?;
+        let validation_data = self
+            .sign_validation_data(authority, wallet, nonce, calls, &validation_hash)
+            .await?;
+
+        self.build_execute_with_validator_transaction(wallet, authorization, calls, validation_data)
+            .await
+    }
+
+    /// Build a transaction that only applies an authorization, without executing any call
+    pub fn build_delegation_transaction(&self, authorization: SignedAuthorization) -> Result<TransactionRequest> {
+        let authority = authorization.recover_authority()?;
+
+        Ok(TransactionRequest::default()
+            .with_to(authority)
+            .with_authorization_list(vec![authorization]))
+    }
+
+    /// Point `authority` at a new delegate; `sender` submits the transaction and pays gas
+    pub async fn redelegate<A, S>(
+        &self,
+        authority: &A,
+        delegate: Address,
+        sender: &S,
+    ) -> Result<alloy::rpc::types::TransactionReceipt>
+    where
+        A: PayloadSigner,
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let authorization = self
+            .create_delegation_authorization(authority, delegate, sender.address())
+            .await?;
+        let tx = self.build_delegation_transaction(authorization)?;
+        self.send_transaction(tx, sender).await
+    }
+
+    /// Clear the delegation of `authority`; `sender` submits the transaction and pays gas
+    pub async fn revoke_delegation<A, S>(
+        &self,
+        authority: &A,
+        sender: &S,
+    ) -> Result<alloy::rpc::types::TransactionReceipt>
+    where
+        A: PayloadSigner,
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        self.redelegate(authority, Address::ZERO, sender).await
     }
 
     /// Send a transaction and wait for receipt
-    pub async fn send_transaction(
+    ///
+    /// Nonce, gas and fees are filled before sending. With a confirmation timeout in the
+    /// [`ReplacementPolicy`](crate::core::ReplacementPolicy), a transaction that is not mined
+    /// in time is replaced with the same nonce, authorization list and higher fees. Every
+    /// attempt is recorded in the [`AuditLog`].
+    ///
+    /// Revert data returned while submitting is decoded into [`Error::ExecutionReverted`];
+    /// [`Error::Reverted`] is returned if the transaction is mined but fails.
+    pub async fn send_transaction<S>(
         &self,
         tx: TransactionRequest,
-        signer: &PrivateKeySigner,
-    ) -> Result<alloy::rpc::types::TransactionReceipt> {
-        let provider = ProviderBuilder::new()
-            .wallet(signer.clone())
-            .on_http(self.config.rpc_url.clone());
-        
-        let pending_tx = provider.send_transaction(tx).await?;
-        println!("Transaction submitted: {}", pending_tx.tx_hash());
-        
-        let receipt = pending_tx.get_receipt().await?;
-      
+        signer: &S,
+    ) -> Result<alloy::rpc::types::TransactionReceipt>
+    where
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let tx = self.prepare_transaction(tx, signer.address()).await?;
+        let tx_hash = self.submit_attempt(&tx, signer, AttemptKind::Original, None).await?;
+
+        let receipt = self
+            .confirm_transaction(tx, signer, vec![tx_hash], AttemptKind::FeeBump)
+            .await?;
+        if !receipt.status() {
+            return Err(Error::Reverted {
+                tx_hash: receipt.transaction_hash,
+                block_number: receipt.block_number,
+            });
+        }
+
         Ok(receipt)
     }
 
+    /// Sign and broadcast a transaction without waiting for it to be mined
+    ///
+    /// Revert data returned while submitting is decoded into [`Error::ExecutionReverted`].
+    pub async fn submit_transaction<S>(
+        &self,
+        mut tx: TransactionRequest,
+        signer: &S,
+    ) -> Result<PendingTransactionBuilder<Ethereum>>
+    where
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        tx.from.get_or_insert(signer.address());
+        self.fill_gas_and_fees(&mut tx).await?;
+
+        // Layer the signer on top of the shared provider; the transport is reused
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer.clone()))
+            .on_provider(&self.provider);
+
+        let calls = revert::batch_calls(&tx);
+        provider
+            .send_transaction(tx)
+            .await
+            .map_err(|e| Error::from(e).decode_revert(&calls))
+    }
+
     /// Get token balance for an address
     pub async fn get_token_balance(&self, address: Address) -> Result<U256> {
-        let provider = ProviderBuilder::new()
-            .on_http(self.config.rpc_url.clone());
-        let erc20 = ERC20::new(self.config.token_address, provider);
+        self.get_balance_of(self.config.token_address, address).await
+    }
+
+    /// Get the balance of `address` for any ERC20 token
+    pub async fn get_balance_of(&self, token: Address, address: Address) -> Result<U256> {
+        let erc20 = ERC20::new(token, &self.provider);
         Ok(erc20.balanceOf(address).call().await?._0)
     }
 
+    /// Get the number of decimals of an ERC20 token
+    pub async fn get_token_decimals(&self, token: Address) -> Result<u8> {
+        let erc20 = ERC20::new(token, &self.provider);
+        Ok(erc20.decimals().call().await?._0)
+    }
+
     /// Get ETH balance for an address
     pub async fn get_eth_balance(&self, address: Address) -> Result<U256> {
-        let provider = ProviderBuilder::new()
-            .on_http(self.config.rpc_url.clone());
-        Ok(provider.get_balance(address).await?)
+        Ok(self.provider.get_balance(address).await?)
     }
 
     /// Print balances for debugging
     pub async fn print_balances(&self, label: &str) -> Result<()> {
         println!("\n=== {} ===", label);
-        
+
         let alice_eth = self.get_eth_balance(self.config.alice_signer.address()).await?;
         let bob_eth = self.get_eth_balance(self.config.bob_signer.address()).await?;
         let receiver_eth = self.get_eth_balance(self.config.receiver_address).await?;
-        
+
         let alice_tokens = self.get_token_balance(self.config.alice_signer.address()).await?;
         let bob_tokens = self.get_token_balance(self.config.bob_signer.address()).await?;
         let receiver_tokens = self.get_token_balance(self.config.receiver_address).await?;
-        
+
         println!("Alice - ETH: {}, Tokens: {}", alice_eth, alice_tokens);
         println!("Bob - ETH: {}, Tokens: {}", bob_eth, bob_tokens);
         println!("Receiver - ETH: {}, Tokens: {}", receiver_eth, receiver_tokens);
-        
+
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+/// Check that an authorization was signed by the EOA the transaction targets
+fn ensure_authority(authorization: &SignedAuthorization, expected: Address) -> Result<()> {
+    let actual = authorization.recover_authority()?;
+    if actual != expected {
+        return Err(Error::AuthorizationMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{address, b256, bytes, keccak256, U64},
+        providers::ProviderBuilder,
+        transports::mock::Asserter,
+    };
+
+    use super::*;
+
+    fn builder(chain_id: Option<u64>, asserter: &Asserter) -> Eip7702Builder<impl Provider> {
+        let config = Config {
+            chain_id,
+            ..Config::for_tests()
+        };
+        Eip7702Builder::with_provider(config, ProviderBuilder::new().on_mocked_client(asserter.clone()))
+    }
+
+    #[tokio::test]
+    async fn chain_id_matches_profile() {
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(31337));
+        let builder = builder(Some(31337), &asserter);
+
+        assert_eq!(builder.chain_id().await.unwrap(), 31337);
+        // The checked chain id is cached
+        assert_eq!(builder.chain_id().await.unwrap(), 31337);
+    }
+
+    #[tokio::test]
+    async fn chain_id_mismatch_fails() {
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(1));
+        let builder = builder(Some(31337), &asserter);
+
+        assert!(matches!(
+            builder.chain_id().await,
+            Err(Error::ChainIdMismatch {
+                expected: 31337,
+                actual: 1
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn chain_id_without_profile_comes_from_node() {
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(11155111));
+        let builder = builder(None, &asserter);
+
+        assert_eq!(builder.chain_id().await.unwrap(), 11155111);
+    }
+
+    #[test]
+    fn validation_hash_matches_fixed_vector() {
+        let wallet = address!("0x1111111111111111111111111111111111111111");
+        let nonce = U256::from(7);
+        let calls = vec![
+            Call {
+                target: address!("0x2222222222222222222222222222222222222222"),
+                value: U256::from(1_000_000_000_000_000_000u128),
+                data: Bytes::new(),
+            },
+            Call {
+                target: address!("0x3333333333333333333333333333333333333333"),
+                value: U256::ZERO,
+                data: bytes!("deadbeef"),
+            },
+        ];
+
+        // EIP-712 encoding spelled out by hand so a change to the domain, the typehash or
+        // the struct layout cannot slip through by changing both sides at once
+        let domain_typehash =
+            keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)");
+        let domain_separator = keccak256(
+            [
+                domain_typehash.as_slice(),
+                keccak256("wallet-core").as_slice(),
+                keccak256("1.0.0").as_slice(),
+                &U256::from(11155111).to_be_bytes::<32>(),
+                wallet.into_word().as_slice(),
+            ]
+            .concat(),
+        );
+        let call_typehash = keccak256("Call(address target,uint256 value,bytes data)");
+        let call_hashes: Vec<u8> = calls
+            .iter()
+            .flat_map(|call| {
+                keccak256(
+                    [
+                        call_typehash.as_slice(),
+                        call.target.into_word().as_slice(),
+                        &call.value.to_be_bytes::<32>(),
+                        keccak256(&call.data).as_slice(),
+                    ]
+                    .concat(),
+                )
+            })
+            .collect();
+        let execute_typehash =
+            keccak256("Execute(uint256 nonce,Call[] calls)Call(address target,uint256 value,bytes data)");
+        let struct_hash = keccak256(
+            [execute_typehash.as_slice(), &nonce.to_be_bytes::<32>(), keccak256(&call_hashes).as_slice()].concat(),
+        );
+        let expected = keccak256([&[0x19, 0x01], domain_separator.as_slice(), struct_hash.as_slice()].concat());
+
+        let builder = Eip7702Builder::with_provider(
+            Config::for_tests(),
+            ProviderBuilder::new().on_mocked_client(Asserter::new()),
+        );
+        let hash = builder.compute_validation_hash(11155111, wallet, nonce, &calls);
+
+        assert_eq!(hash, expected);
+        // Pinned from the encoding above; it has not been compared with Sepolia's
+        // `getValidationTypedHash` for this input, which needs a node
+        assert_eq!(hash, b256!("0x8e9553bd7087d2728fe2d8928396d0531462beaffa6359bf5066990729da003b"));
+    }
+}
@@ -0,0 +1,262 @@
+use alloy::{
+    dyn_abi::{DynSolType, DynSolValue, JsonAbiExt, Specifier},
+    json_abi::Function,
+    primitives::{Address, Bytes, U256},
+    sol_types::SolCall,
+};
+use serde_json::Value;
+
+use crate::core::{
+    contracts::{Call, ERC20},
+    error::{Error, Result},
+};
+
+/// Largest integer a JSON number is accepted for, `Number.MAX_SAFE_INTEGER` (2^53 - 1)
+///
+/// JSON producers such as JavaScript store numbers as doubles, so larger integers may already
+/// have been rounded; they must be passed as decimal or hex strings.
+pub const MAX_SAFE_JSON_INTEGER: u64 = (1 << 53) - 1;
+
+/// Builds the [`Call`]s of a WalletCore batch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallBuilder {
+    calls: Vec<Call>,
+}
+
+impl CallBuilder {
+    /// Start an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an already built call
+    pub fn push(mut self, call: Call) -> Self {
+        self.calls.push(call);
+        self
+    }
+
+    /// Send native ETH to `to`
+    pub fn eth(self, to: Address, value: U256) -> Self {
+        self.raw(to, value, Bytes::new())
+    }
+
+    /// Call `target` with raw calldata
+    pub fn raw(self, target: Address, value: U256, data: impl Into<Bytes>) -> Self {
+        self.push(Call {
+            target,
+            value,
+            data: data.into(),
+        })
+    }
+
+    /// Call `target` with a `sol!`-generated call struct
+    pub fn call<C: SolCall>(self, target: Address, call: C) -> Self {
+        self.call_with_value(target, U256::ZERO, call)
+    }
+
+    /// Call a payable function of `target` with a `sol!`-generated call struct
+    pub fn call_with_value<C: SolCall>(self, target: Address, value: U256, call: C) -> Self {
+        self.raw(target, value, call.abi_encode())
+    }
+
+    /// Transfer `amount` of `token` to `to`
+    pub fn erc20_transfer(self, token: Address, to: Address, amount: U256) -> Self {
+        self.call(
+            token,
+            ERC20::transferCall {
+                recipient: to,
+                amount,
+            },
+        )
+    }
+
+    /// Allow `spender` to move `amount` of `token`
+    pub fn erc20_approve(self, token: Address, spender: Address, amount: U256) -> Self {
+        self.call(token, ERC20::approveCall { spender, amount })
+    }
+
+    /// Move `amount` of `token` from `from` to `to` using an allowance
+    pub fn erc20_transfer_from(self, token: Address, from: Address, to: Address, amount: U256) -> Self {
+        self.call(
+            token,
+            ERC20::transferFromCall {
+                sender: from,
+                recipient: to,
+                amount,
+            },
+        )
+    }
+
+    /// Call `target`, sending `value`, with calldata encoded from a human-readable signature
+    /// and JSON arguments, e.g. `"approve(address,uint256)"` with `["0x…", "1000"]`
+    pub fn function(self, target: Address, value: U256, signature: &str, args: &[Value]) -> Result<Self> {
+        let data = encode_function_call(signature, args)?;
+        Ok(self.raw(target, value, data))
+    }
+
+    /// Get the calls added so far
+    pub fn calls(&self) -> &[Call] {
+        &self.calls
+    }
+
+    /// Finish the batch
+    pub fn build(self) -> Vec<Call> {
+        self.calls
+    }
+}
+
+/// Encode calldata from a human-readable function signature and JSON arguments
+///
+/// The signature may omit or include parameter names and the `function` keyword.
+/// Numbers can be given as decimal or hex strings, or as JSON integers up to
+/// [`MAX_SAFE_JSON_INTEGER`] in absolute value; arrays and tuples are given as JSON arrays.
+pub fn encode_function_call(signature: &str, args: &[Value]) -> Result<Bytes> {
+    let invalid = |reason: String| Error::InvalidCall {
+        signature: signature.to_string(),
+        reason,
+    };
+
+    let function = Function::parse(signature).map_err(|e| invalid(e.to_string()))?;
+    if function.inputs.len() != args.len() {
+        return Err(invalid(format!(
+            "expected {} arguments, got {}",
+            function.inputs.len(),
+            args.len()
+        )));
+    }
+
+    let values = function
+        .inputs
+        .iter()
+        .zip(args)
+        .map(|(param, arg)| {
+            let ty = param.resolve().map_err(|e| invalid(e.to_string()))?;
+            json_to_sol(&ty, arg).map_err(|e| invalid(format!("argument `{}`: {}", param.name, e)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let data = function
+        .abi_encode_input(&values)
+        .map_err(|e| invalid(e.to_string()))?;
+    Ok(data.into())
+}
+
+/// Convert a JSON value into a Solidity value of type `ty`
+fn json_to_sol(ty: &DynSolType, value: &Value) -> std::result::Result<DynSolValue, String> {
+    match (ty, value) {
+        (DynSolType::Array(inner), Value::Array(items)) => items
+            .iter()
+            .map(|item| json_to_sol(inner, item))
+            .collect::<std::result::Result<_, _>>()
+            .map(DynSolValue::Array),
+        (DynSolType::FixedArray(inner, len), Value::Array(items)) => {
+            if items.len() != *len {
+                return Err(format!("expected {} items, got {}", len, items.len()));
+            }
+            items
+                .iter()
+                .map(|item| json_to_sol(inner, item))
+                .collect::<std::result::Result<_, _>>()
+                .map(DynSolValue::FixedArray)
+        }
+        (DynSolType::Tuple(types), Value::Array(items)) => {
+            if items.len() != types.len() {
+                return Err(format!("expected {} tuple fields, got {}", types.len(), items.len()));
+            }
+            types
+                .iter()
+                .zip(items)
+                .map(|(ty, item)| json_to_sol(ty, item))
+                .collect::<std::result::Result<_, _>>()
+                .map(DynSolValue::Tuple)
+        }
+        (_, Value::String(s)) => ty.coerce_str(s).map_err(|e| e.to_string()),
+        (_, Value::Number(n)) => {
+            let safe = n.as_u64().is_some_and(|n| n <= MAX_SAFE_JSON_INTEGER)
+                || n.as_i64().is_some_and(|n| n.unsigned_abs() <= MAX_SAFE_JSON_INTEGER);
+            if !safe {
+                return Err(format!("{} is not an integer below 2^53, pass it as a string", n));
+            }
+            ty.coerce_str(&n.to_string()).map_err(|e| e.to_string())
+        }
+        (_, Value::Bool(b)) => ty.coerce_str(&b.to_string()).map_err(|e| e.to_string()),
+        _ => Err(format!("cannot convert {} to {}", value, ty)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{address, bytes, hex};
+    use serde_json::json;
+
+    use super::*;
+
+    const TOKEN: Address = address!("0x2000000000000000000000000000000000000000");
+    const RECEIVER: Address = address!("0x3000000000000000000000000000000000000000");
+
+    #[test]
+    fn encodes_a_known_transfer() {
+        let data = encode_function_call("transfer(address,uint256)", &[json!(RECEIVER), json!(1000)]).unwrap();
+
+        assert_eq!(data[..4], hex!("a9059cbb"));
+        assert_eq!(
+            data,
+            bytes!(
+                "a9059cbb"
+                "0000000000000000000000003000000000000000000000000000000000000000"
+                "00000000000000000000000000000000000000000000000000000000000003e8"
+            )
+        );
+        let built = CallBuilder::new().erc20_transfer(TOKEN, RECEIVER, U256::from(1000)).build();
+        assert_eq!(built[0].data, data);
+    }
+
+    #[test]
+    fn rejects_argument_count_mismatch() {
+        let error = encode_function_call("transfer(address,uint256)", &[json!(RECEIVER)]).unwrap_err();
+        assert!(matches!(error, Error::InvalidCall { reason, .. } if reason == "expected 2 arguments, got 1"));
+    }
+
+    #[test]
+    fn coerces_tuples_and_arrays() {
+        let data = encode_function_call(
+            "function f((address,uint256)[] transfers, uint8[2] flags)",
+            &[json!([[RECEIVER, "0x10"], [TOKEN, 7]]), json!([1, "2"])],
+        )
+        .unwrap();
+
+        let function = Function::parse("f((address,uint256)[],uint8[2])").unwrap();
+        let expected = function
+            .abi_encode_input(&[
+                DynSolValue::Array(vec![
+                    DynSolValue::Tuple(vec![RECEIVER.into(), U256::from(16).into()]),
+                    DynSolValue::Tuple(vec![TOKEN.into(), U256::from(7).into()]),
+                ]),
+                DynSolValue::FixedArray(vec![DynSolValue::Uint(U256::from(1), 8), DynSolValue::Uint(U256::from(2), 8)]),
+            ])
+            .unwrap();
+        assert_eq!(data, Bytes::from(expected));
+    }
+
+    #[test]
+    fn rejects_wrong_tuple_and_array_lengths() {
+        assert!(encode_function_call("f((address,uint256))", &[json!([RECEIVER])]).is_err());
+        assert!(encode_function_call("f(uint8[2])", &[json!([1, 2, 3])]).is_err());
+        assert!(encode_function_call("f(uint256[])", &[json!(1)]).is_err());
+    }
+
+    #[test]
+    fn large_integers_must_be_strings() {
+        let max = MAX_SAFE_JSON_INTEGER;
+        assert!(encode_function_call("f(uint256)", &[json!(max)]).is_ok());
+        assert!(encode_function_call("f(int256)", &[json!(-(max as i64))]).is_ok());
+
+        assert!(encode_function_call("f(uint256)", &[json!(max + 1)]).is_err());
+        assert!(encode_function_call("f(uint256)", &[json!(u64::MAX)]).is_err());
+        assert!(encode_function_call("f(uint256)", &[json!(1e20)]).is_err());
+        assert!(encode_function_call("f(uint256)", &[json!(1.5)]).is_err());
+
+        let data = encode_function_call("f(uint256)", &[json!("100000000000000000000")]).unwrap();
+        assert_eq!(U256::from_be_slice(&data[4..]), U256::from(10).pow(U256::from(20)));
+    }
+}
@@ -1,56 +1,312 @@
-use std::str::FromStr;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 use alloy::{
-    primitives::{Address, address},
-    transports::http::reqwest::Url,
+    primitives::Address,
+    providers::{Provider, ProviderBuilder},
     signers::local::PrivateKeySigner,
+    transports::http::reqwest::Url,
+};
+use crate::core::{
+    error::{Error, Result},
+    profile::{ConfigFile, FeePolicy, NetworkProfile, ReplacementPolicy},
+    registry,
+    remote_signer::RemoteSigner,
+    signer::{self, WalletSigner},
+    types::{Addresses, AuthorizationChain},
+};
+
+/// Network used when none is selected
+pub const DEFAULT_NETWORK: &str = "sepolia";
+
+/// Config files looked up in the working directory when none is given
+const DEFAULT_CONFIG_FILES: [&str; 2] = ["tx7702.toml", "tx7702.json"];
+
+/// Environment variables that select the signer of one account
+///
+/// They are tried in order: remote signer, keystore, mnemonic, then raw private key.
+struct SignerEnv {
+    /// URL of a remote signer service holding the key
+    remote_signer_url: &'static str,
+    /// Address of the account, required with a remote signer
+    address: &'static str,
+    /// Path of an encrypted JSON keystore
+    keystore: &'static str,
+    keystore_password: &'static str,
+    /// File holding the keystore password, read when the password is not set directly
+    keystore_password_file: &'static str,
+    /// BIP-39 mnemonic phrase
+    mnemonic: &'static str,
+    derivation_path: &'static str,
+    private_key: &'static str,
+}
+
+const ALICE_SIGNER: SignerEnv = SignerEnv {
+    remote_signer_url: "ALICE_REMOTE_SIGNER_URL",
+    address: "ALICE_ADDRESS",
+    keystore: "ALICE_KEYSTORE",
+    keystore_password: "ALICE_KEYSTORE_PASSWORD",
+    keystore_password_file: "ALICE_KEYSTORE_PASSWORD_FILE",
+    mnemonic: "ALICE_MNEMONIC",
+    derivation_path: "ALICE_DERIVATION_PATH",
+    private_key: "ALICE_PRIVATE_KEY",
+};
+
+const BOB_SIGNER: SignerEnv = SignerEnv {
+    remote_signer_url: "BOB_REMOTE_SIGNER_URL",
+    address: "BOB_ADDRESS",
+    keystore: "BOB_KEYSTORE",
+    keystore_password: "BOB_KEYSTORE_PASSWORD",
+    keystore_password_file: "BOB_KEYSTORE_PASSWORD_FILE",
+    mnemonic: "BOB_MNEMONIC",
+    derivation_path: "BOB_DERIVATION_PATH",
+    private_key: "BOB_PRIVATE_KEY",
 };
-use eyre::Result;
 
 /// Configuration for the EIP-7702 demo
 #[derive(Debug, Clone)]
 pub struct Config {
+    /// Name of the selected network profile
+    pub network: String,
     pub rpc_url: Url,
-    pub alice_signer: PrivateKeySigner,
-    pub bob_signer: PrivateKeySigner,
+    /// Chain id declared by the network profile
+    pub chain_id: Option<u64>,
+    pub alice_signer: WalletSigner,
+    pub bob_signer: WalletSigner,
     pub receiver_address: Address,
     pub wallet_core_address: Address,
+    pub validator_address: Address,
     pub token_address: Address,
+    /// Known tokens of the network by symbol
+    pub tokens: BTreeMap<String, Address>,
+    pub fee_policy: FeePolicy,
+    /// Replacement of transactions that are not mined in time
+    pub replacement_policy: ReplacementPolicy,
+    /// JSON lines file recording every transaction attempt
+    pub audit_log: Option<PathBuf>,
+    /// Chain binding of the authorizations signed by the builder
+    pub authorization_chain: AuthorizationChain,
+    /// Allow signing authorizations for delegates missing from the registry
+    pub allow_unknown_delegate: bool,
 }
 
 impl Config {
-    /// Load configuration from environment variables
+    /// Load configuration from environment variables, on top of the default network profile
     pub fn from_env() -> Result<Self> {
-        dotenv::dotenv().ok();
+        Self::load(None, None)
+    }
 
-        let rpc_url_str = std::env::var("SEPOLIA_RPC_URL")
-            .expect("SEPOLIA_RPC_URL must be set in .env file");
-        let rpc_url = Url::parse(&rpc_url_str).expect("Invalid RPC URL");
+    /// Load configuration from a config file and network profile, with environment overrides.
+    ///
+    /// The file defaults to `$TX7702_CONFIG`, then `tx7702.toml` or `tx7702.json` if present.
+    /// The network defaults to `$TX7702_NETWORK`, then the file's `default_network`, then `sepolia`.
+    /// A missing WalletCore address is taken from the registry for the profile's chain id.
+    pub fn load(config_path: Option<&Path>, network: Option<&str>) -> Result<Self> {
+        let (network, profile) = load_profile(config_path, network)?;
+        Self::from_profile(network, profile)
+    }
 
-        let alice_private_key = std::env::var("ALICE_PRIVATE_KEY")
-            .expect("ALICE_PRIVATE_KEY must be set in .env file");
-        let alice_signer: PrivateKeySigner = alice_private_key.parse()?;
+    /// Like [`Config::load`], but asks the RPC for the chain id when the profile does not declare one
+    pub async fn connect(config_path: Option<&Path>, network: Option<&str>) -> Result<Self> {
+        let (network, mut profile) = load_profile(config_path, network)?;
+        if profile.chain_id.is_none() {
+            let rpc_url = profile.rpc_url.clone().ok_or(Error::MissingConfig("RPC_URL"))?;
+            let provider = ProviderBuilder::new().on_http(rpc_url);
+            profile.chain_id = Some(provider.get_chain_id().await?);
+        }
+        Self::from_profile(network, profile)
+    }
 
-        let bob_private_key = std::env::var("BOB_PRIVATE_KEY")
-            .expect("BOB_PRIVATE_KEY must be set in .env file");
-        let bob_signer: PrivateKeySigner = bob_private_key.parse()?;
+    /// Resolve a token given by symbol from the network profile, or by address
+    pub fn token(&self, token: &str) -> Result<Address> {
+        if let Some(address) = self.tokens.get(token) {
+            return Ok(*address);
+        }
+        token.parse().map_err(|_| Error::InvalidConfig {
+            name: "token",
+            reason: format!("`{}` is neither a known token symbol nor an address", token),
+        })
+    }
 
-        let receiver_address_str = std::env::var("RECEIVER_ADDRESS")
-            .expect("RECEIVER_ADDRESS must be set in .env file");
-        let receiver_address = Address::from_str(&receiver_address_str)?;
+    /// Build the configuration from a resolved network profile
+    fn from_profile(network: String, profile: NetworkProfile) -> Result<Self> {
+        let deployment = profile.chain_id.and_then(registry::latest);
 
-        // Default addresses - these could also be environment variables
-        let wallet_core_address = address!("0x80296FF8D1ED46f8e3C7992664D13B833504c2Bb");
+        let rpc_url = profile.rpc_url.clone().ok_or(Error::MissingConfig("RPC_URL"))?;
+        let wallet_core_address = profile
+            .wallet_core_address
+            .or(deployment.map(|deployment| deployment.wallet_core))
+            .ok_or(Error::MissingConfig("WALLET_CORE_ADDRESS"))?;
+        let validator_address = profile
+            .validator_address
+            .or(deployment.map(|deployment| deployment.ecdsa_validator))
+            .unwrap_or(Addresses::ECDSA_VALIDATOR);
+        let token_address = parse_env("TOKEN_ADDRESS")?
+            .or(profile.default_token_address())
+            .ok_or(Error::MissingConfig("TOKEN_ADDRESS"))?;
 
-        // use link token on sepolia as example
-        let token_address = address!("0x779877A7B0D9E8603169DdbD7836e478b4624789");
+        let alice_signer = load_signer(&ALICE_SIGNER)?;
+        let bob_signer = load_signer(&BOB_SIGNER)?;
+        let receiver_address = parse_env("RECEIVER_ADDRESS")?.ok_or(Error::MissingConfig("RECEIVER_ADDRESS"))?;
 
         Ok(Config {
+            network,
             rpc_url,
+            chain_id: profile.chain_id,
             alice_signer,
             bob_signer,
             receiver_address,
             wallet_core_address,
+            validator_address,
             token_address,
+            tokens: profile.tokens,
+            fee_policy: profile.fees,
+            replacement_policy: profile.replacement,
+            audit_log: profile.audit_log,
+            authorization_chain: AuthorizationChain::Current,
+            allow_unknown_delegate: profile.allow_unknown_delegate.unwrap_or(false),
         })
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+impl Config {
+    /// Config of the built-in `anvil` profile with random keys, for unit tests
+    pub(crate) fn for_tests() -> Config {
+        Config {
+            network: "anvil".to_string(),
+            rpc_url: "http://127.0.0.1:8545".parse().expect("valid URL"),
+            chain_id: Some(31337),
+            alice_signer: PrivateKeySigner::random().into(),
+            bob_signer: PrivateKeySigner::random().into(),
+            receiver_address: Address::repeat_byte(0x33),
+            wallet_core_address: Address::repeat_byte(0x77),
+            validator_address: Addresses::ECDSA_VALIDATOR,
+            token_address: Address::repeat_byte(0x20),
+            tokens: BTreeMap::new(),
+            fee_policy: FeePolicy::default(),
+            replacement_policy: ReplacementPolicy::default(),
+            audit_log: None,
+            authorization_chain: AuthorizationChain::Current,
+            allow_unknown_delegate: true,
+        }
+    }
+}
+
+/// Read the config file and resolve the selected network profile, with environment overrides applied
+fn load_profile(config_path: Option<&Path>, network: Option<&str>) -> Result<(String, NetworkProfile)> {
+    dotenv::dotenv().ok();
+
+    let config_path = config_path
+        .map(Path::to_path_buf)
+        .or_else(|| optional_env("TX7702_CONFIG").map(PathBuf::from))
+        .or_else(|| {
+            DEFAULT_CONFIG_FILES
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.exists())
+        });
+    let file = match config_path {
+        Some(path) => ConfigFile::load(&path)?,
+        None => ConfigFile::default(),
+    };
+
+    let network = network
+        .map(str::to_string)
+        .or_else(|| optional_env("TX7702_NETWORK"))
+        .or_else(|| file.default_network.clone())
+        .unwrap_or_else(|| DEFAULT_NETWORK.to_string());
+    let profile = file.profile(&network)?;
+
+    // Environment variables override the profile
+    let legacy_rpc_url = if network == "sepolia" { parse_env("SEPOLIA_RPC_URL")? } else { None };
+    let overrides = NetworkProfile {
+        rpc_url: parse_env("RPC_URL")?.or(legacy_rpc_url),
+        chain_id: parse_env("CHAIN_ID")?,
+        wallet_core_address: parse_env("WALLET_CORE_ADDRESS")?,
+        validator_address: parse_env("VALIDATOR_ADDRESS")?,
+        fees: FeePolicy {
+            strategy: parse_env("FEE_STRATEGY")?,
+            max_fee_per_gas: parse_env("MAX_FEE_PER_GAS")?,
+            max_priority_fee_per_gas: parse_env("MAX_PRIORITY_FEE_PER_GAS")?,
+            reward_percentile: parse_env("FEE_REWARD_PERCENTILE")?,
+            fee_history_blocks: parse_env("FEE_HISTORY_BLOCKS")?,
+            max_fee_per_gas_cap: parse_env("MAX_FEE_PER_GAS_CAP")?,
+            max_total_cost: parse_env("MAX_TOTAL_COST")?,
+        },
+        replacement: ReplacementPolicy {
+            confirmation_timeout_secs: parse_env("CONFIRMATION_TIMEOUT_SECS")?,
+            fee_bump_percent: parse_env("FEE_BUMP_PERCENT")?,
+            max_fee_bumps: parse_env("MAX_FEE_BUMPS")?,
+        },
+        audit_log: optional_env("AUDIT_LOG").map(PathBuf::from),
+        allow_unknown_delegate: parse_env("ALLOW_UNKNOWN_DELEGATE")?,
+        ..Default::default()
+    };
+
+    Ok((network, profile.merge(overrides)))
+}
+
+/// Read an optional environment variable
+fn optional_env(name: &'static str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Read and parse an optional environment variable
+fn parse_env<T>(name: &'static str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    optional_env(name)
+        .map(|value| {
+            value.parse().map_err(|e: T::Err| Error::InvalidConfig {
+                name,
+                reason: e.to_string(),
+            })
+        })
+        .transpose()
+}
+
+/// Load the signer of one account from the environment
+fn load_signer(env: &SignerEnv) -> Result<WalletSigner> {
+    if let Some(url) = parse_env(env.remote_signer_url)? {
+        let address = parse_env(env.address)?.ok_or(Error::MissingConfig(env.address))?;
+        return Ok(RemoteSigner::new(url, address).into());
+    }
+
+    if let Some(keystore) = optional_env(env.keystore) {
+        let password = match optional_env(env.keystore_password) {
+            Some(password) => password,
+            None => {
+                let path = optional_env(env.keystore_password_file).ok_or(Error::MissingConfig(env.keystore_password))?;
+                std::fs::read_to_string(&path)
+                    .map_err(|e| Error::InvalidConfig {
+                        name: env.keystore_password_file,
+                        reason: format!("{}: {}", path, e),
+                    })?
+                    .trim_end()
+                    .to_string()
+            }
+        };
+        return signer::from_keystore(Path::new(&keystore), password)
+            .map(WalletSigner::from)
+            .map_err(|source| Error::InvalidKey { name: env.keystore, source });
+    }
+
+    if let Some(mnemonic) = optional_env(env.mnemonic) {
+        let derivation_path = optional_env(env.derivation_path);
+        return signer::from_mnemonic(&mnemonic, derivation_path.as_deref())
+            .map(WalletSigner::from)
+            .map_err(|source| Error::InvalidKey { name: env.mnemonic, source });
+    }
+
+    optional_env(env.private_key)
+        .ok_or(Error::MissingConfig(env.private_key))?
+        .parse::<PrivateKeySigner>()
+        .map(WalletSigner::from)
+        .map_err(|source| Error::InvalidKey { name: env.private_key, source })
+}
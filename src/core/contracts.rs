@@ -1,41 +1,68 @@
 use alloy::sol;
 
-// Contract interfaces generated from ABI
-sol! {
+// WalletCore, its main storage and validators, generated from the ABIs in contracts/abi; the
+// storage and validator ABIs are hand-written subsets, see the README
+sol!(
     #[allow(missing_docs)]
     #[sol(rpc)]
-    struct Call {
-        address target;
-        uint256 value;
-        bytes data;
-    }
+    #[derive(Debug, PartialEq, Eq)]
+    IWalletCore,
+    "contracts/abi/IWalletCore.json"
+);
 
+sol!(
     #[allow(missing_docs)]
     #[sol(rpc)]
-    contract IWalletCore {
-        function initialize() external; 
-        function getMainStorage() external view returns (address);
-        function executeWithValidator(Call[] calldata calls, address validator, bytes calldata validateData) external;
-        function getNonce(address sender) external view returns (uint256);
-        function getValidationTypedHash(uint256 nonce, Call[] calldata calls) external view returns (bytes32);
-        function addValidator(
-            address validatorImpl,
-            bytes calldata immutableArgs
-        ) external;
-    }
+    IStorage,
+    "contracts/abi/IStorage.json"
+);
 
+sol!(
     #[allow(missing_docs)]
     #[sol(rpc)]
-    contract IStorage {
-        function getNonce() external view returns (uint256);
-    }
+    IValidator,
+    "contracts/abi/IValidator.json"
+);
 
+pub use IWalletCore::{Call, Session};
+
+// Other contract interfaces
+sol! {
     #[allow(missing_docs)]
     #[sol(rpc)]
+    #[derive(Debug, PartialEq, Eq)]
     contract ERC20 {
+        error ERC20InsufficientBalance(address sender, uint256 balance, uint256 needed);
+        error ERC20InvalidSender(address sender);
+        error ERC20InvalidReceiver(address receiver);
+        error ERC20InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
+        error ERC20InvalidApprover(address approver);
+        error ERC20InvalidSpender(address spender);
+
+        event Transfer(address indexed from, address indexed to, uint256 value);
+
+        function symbol() public view virtual override returns (string memory);
+        function decimals() public view virtual override returns (uint8);
         function approve(address spender, uint256 amount) public virtual override returns (bool);
         function transfer(address recipient, uint256 amount) public virtual override returns (bool);
         function balanceOf(address account) public view virtual override returns (uint256);
         function transferFrom(address sender, address recipient, uint256 amount) public virtual override returns (bool);
     }
-} 
\ No newline at end of file
+
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Result[] memory returnData);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    collections::BTreeMap,
+    io::ErrorKind as IoErrorKind,
+    path::{Path, PathBuf},
+};
+
+use alloy::{
+    hex,
+    network::{EthereumWallet, TransactionBuilder},
+    node_bindings::{Anvil, AnvilInstance},
+    primitives::{uint, Address, Bytes, U256},
+    providers::{ext::AnvilApi, Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+    sol,
+    sol_types::SolConstructor,
+};
+
+use crate::core::{
+    config::Config,
+    contracts::IWalletCore,
+    error::{Error, Result},
+    profile::{FeePolicy, ReplacementPolicy},
+    typed_data::{WALLET_CORE_DOMAIN_NAME, WALLET_CORE_DOMAIN_VERSION},
+    types::{Addresses, AuthorizationChain},
+    Eip7702Builder,
+};
+
+/// Directory holding the creation bytecode deployed on a devnet, one hex `<Contract>.bin` per contract
+pub const ARTIFACTS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/contracts/bytecode");
+
+/// Contracts deployed on every devnet, in deployment order
+///
+/// `MainStorage` is the implementation WalletCore clones into each wallet's storage. With the
+/// deployer's nonces starting at 0, the contracts land at the addresses of the `anvil` profile
+/// in `tx7702.example.toml`.
+pub const CONTRACTS: [&str; 3] = ["MainStorage", "WalletCore", "MockERC20"];
+
+/// Runtime bytecode placed at [`Addresses::MULTICALL3`], which a fresh Anvil node does not have
+pub const MULTICALL3: &str = "Multicall3";
+
+/// Symbol of the mock token in the devnet config
+pub const MOCK_TOKEN: &str = "MOCK";
+
+/// Mock tokens minted to Alice and Bob: 1000 tokens with 18 decimals
+pub const INITIAL_TOKEN_BALANCE: U256 = uint!(1_000_000_000_000_000_000_000_U256);
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract MockERC20 {
+        function mint(address to, uint256 amount) external;
+    }
+}
+
+/// Local Anvil node with WalletCore, Multicall3 and a mock token deployed
+///
+/// Anvil's first four dev accounts are the deployer, Alice, Bob and the receiver. The node
+/// is stopped when the devnet is dropped.
+pub struct Devnet {
+    anvil: AnvilInstance,
+    deployer: PrivateKeySigner,
+    config: Config,
+}
+
+impl Devnet {
+    /// Start Anvil with the Prague hardfork, deploy the contracts and mint mock tokens to
+    /// Alice and Bob
+    ///
+    /// Fails with [`Error::MissingArtifact`] when a contract in [`CONTRACTS`] or [`MULTICALL3`]
+    /// has no bytecode
+    /// and with [`Error::Anvil`] when `anvil` cannot be started.
+    pub async fn spawn() -> Result<Devnet> {
+        let main_storage_code = bytecode("MainStorage")?;
+        let wallet_core_code = bytecode("WalletCore")?;
+        let token_code = bytecode("MockERC20")?;
+        let multicall3_code = bytecode(MULTICALL3)?;
+
+        let anvil = Anvil::new().arg("--hardfork").arg("prague").try_spawn()?;
+        let keys: Vec<PrivateKeySigner> = anvil.keys()[..4].iter().cloned().map(PrivateKeySigner::from).collect();
+        let [deployer, alice, bob, receiver] = <[PrivateKeySigner; 4]>::try_from(keys).expect("four dev accounts");
+
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(deployer.clone()))
+            .on_http(anvil.endpoint_url());
+
+        provider.anvil_set_code(Addresses::MULTICALL3, multicall3_code).await?;
+        let main_storage = deploy(&provider, main_storage_code).await?;
+        let constructor = IWalletCore::constructorCall {
+            mainStorageImpl: main_storage,
+            name: WALLET_CORE_DOMAIN_NAME.to_string(),
+            version: WALLET_CORE_DOMAIN_VERSION.to_string(),
+        };
+        let wallet_core_code = [wallet_core_code.as_ref(), &constructor.abi_encode()].concat();
+        let wallet_core = deploy(&provider, wallet_core_code.into()).await?;
+        let token = deploy(&provider, token_code).await?;
+
+        let mock = MockERC20::new(token, &provider);
+        for account in [alice.address(), bob.address()] {
+            mock.mint(account, INITIAL_TOKEN_BALANCE).send().await?.get_receipt().await?;
+        }
+
+        let config = Config {
+            network: "devnet".to_string(),
+            rpc_url: anvil.endpoint_url(),
+            chain_id: Some(anvil.chain_id()),
+            alice_signer: alice.into(),
+            bob_signer: bob.into(),
+            receiver_address: receiver.address(),
+            wallet_core_address: wallet_core,
+            validator_address: Addresses::ECDSA_VALIDATOR,
+            token_address: token,
+            tokens: BTreeMap::from([(MOCK_TOKEN.to_string(), token)]),
+            fee_policy: FeePolicy::default(),
+            replacement_policy: ReplacementPolicy::default(),
+            audit_log: None,
+            authorization_chain: AuthorizationChain::Current,
+            // Local deployments are not in the WalletCore registry
+            allow_unknown_delegate: true,
+        };
+
+        Ok(Devnet {
+            anvil,
+            deployer,
+            config,
+        })
+    }
+
+    /// Get the config of the devnet, ready for [`Eip7702Builder::new`]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Get the Anvil node
+    pub fn anvil(&self) -> &AnvilInstance {
+        &self.anvil
+    }
+
+    /// Get the account that deployed the contracts; it holds ETH but no tokens
+    pub fn deployer(&self) -> &PrivateKeySigner {
+        &self.deployer
+    }
+
+    /// Delegate Alice and Bob to WalletCore and create their storage, paid by the deployer
+    pub async fn initialize_wallets(&self) -> Result<()> {
+        let builder = Eip7702Builder::new(self.config.clone());
+        let authorizers = [self.config.alice_signer.clone(), self.config.bob_signer.clone()];
+        let (receipt, outcomes) = builder.initialize_batch(&authorizers, &self.deployer).await?;
+        let failed: Vec<Address> = outcomes
+            .iter()
+            .filter(|outcome| !outcome.is_initialized())
+            .map(|outcome| outcome.authority)
+            .collect();
+        if !failed.is_empty() {
+            return Err(Error::NotInitialized {
+                tx_hash: receipt.transaction_hash,
+                authorities: failed,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Path of the creation bytecode of a contract
+pub fn artifact_path(name: &str) -> PathBuf {
+    Path::new(ARTIFACTS_DIR).join(format!("{}.bin", name))
+}
+
+/// Read the creation bytecode of a contract from [`ARTIFACTS_DIR`]
+pub fn bytecode(name: &str) -> Result<Bytes> {
+    let path = artifact_path(name);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == IoErrorKind::NotFound => return Err(Error::MissingArtifact(path)),
+        Err(e) => return Err(e.into()),
+    };
+
+    hex::decode(contents.trim())
+        .map(Bytes::from)
+        .map_err(|e| Error::InvalidConfig {
+            name: "contract bytecode",
+            reason: format!("{}: {}", path.display(), e),
+        })
+}
+
+/// Deploy a contract and return its address
+async fn deploy<P: Provider>(provider: &P, code: Bytes) -> Result<Address> {
+    let tx = TransactionRequest::default().with_deploy_code(code);
+    let receipt = provider.send_transaction(tx).await?.get_receipt().await?;
+
+    match receipt.contract_address {
+        Some(address) if receipt.status() => Ok(address),
+        _ => Err(Error::Reverted {
+            tx_hash: receipt.transaction_hash,
+            block_number: receipt.block_number,
+        }),
+    }
+}
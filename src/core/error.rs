@@ -0,0 +1,246 @@
+use alloy::{
+    eips::eip7702::Eip7702Error,
+    primitives::{Address, Bytes, FixedBytes, TxHash, U256},
+    node_bindings::NodeError,
+    providers::PendingTransactionError,
+    signers::local::LocalSignerError,
+    transports::TransportError,
+};
+
+use crate::core::{contracts::Call, revert::ExecutionRevert, verify::AuthorizationReport};
+
+/// Errors returned by the core module
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A required configuration value is not set
+    #[error("{0} must be set in the environment or the network profile")]
+    MissingConfig(&'static str),
+
+    /// A configuration value is set but cannot be parsed
+    #[error("invalid value for {name}: {reason}")]
+    InvalidConfig { name: &'static str, reason: String },
+
+    /// A private key, keystore or mnemonic cannot be loaded
+    #[error("invalid key in {name}: {source}")]
+    InvalidKey {
+        name: &'static str,
+        #[source]
+        source: LocalSignerError,
+    },
+
+    /// A call cannot be encoded from a function signature and arguments
+    #[error("cannot encode call to `{signature}`: {reason}")]
+    InvalidCall { signature: String, reason: String },
+
+    /// The node is on another chain than the network profile declares
+    #[error("the network profile declares chain {expected}, but the RPC node is on chain {actual}")]
+    ChainIdMismatch { expected: u64, actual: u64 },
+
+    /// A row of a payout manifest cannot be resolved
+    #[error("invalid payout manifest row {row}: {reason}")]
+    InvalidManifest { row: usize, reason: String },
+
+    /// An account does not hold enough of a token
+    #[error("{account} holds {available} of token {token}, needs {needed}")]
+    InsufficientBalance {
+        token: Address,
+        account: Address,
+        needed: U256,
+        available: U256,
+    },
+
+    /// A single call needs more gas than a batch may use
+    #[error("call needs {gas} gas, more than the batch limit of {max_gas}")]
+    BatchTooLarge { gas: u64, max_gas: u64 },
+
+    /// The bytecode of a contract deployed on a devnet is missing
+    #[error("contract bytecode not found at {}", .0.display())]
+    MissingArtifact(std::path::PathBuf),
+
+    /// A contract the operation relies on has no code on the chain
+    #[error("{name} is not deployed at {address} on this chain")]
+    MissingContract { name: &'static str, address: Address },
+
+    /// A local Anvil node cannot be started
+    #[error("cannot start anvil: {0}")]
+    Anvil(#[from] NodeError),
+
+    /// Calls do not fit the policy of a session key
+    #[error("rejected by the session policy: {0}")]
+    SessionPolicyViolation(String),
+
+    /// A local I/O operation failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The submission queue stopped before the transaction was broadcast
+    #[error("submission queue is closed")]
+    QueueClosed,
+
+    /// No transaction with the nonce was mined, even after replacing it with higher fees
+    #[error("no transaction with nonce {nonce} was mined after {} attempts: {tx_hashes:?}", tx_hashes.len())]
+    ConfirmationTimeout { nonce: u64, tx_hashes: Vec<TxHash> },
+
+    /// A transaction cannot be sped up or cancelled
+    #[error("cannot replace transaction {tx_hash}: {reason}")]
+    CannotReplace { tx_hash: TxHash, reason: String },
+
+    /// The fee caps leave a max fee per gas below what the network needs
+    #[error("max fee per gas is capped at {max_fee_per_gas} wei, the network needs {needed} wei")]
+    FeeCapExceeded { max_fee_per_gas: u128, needed: u128 },
+
+    /// The RPC request failed
+    #[error("RPC request failed: {0}")]
+    Rpc(#[from] TransportError),
+
+    /// A contract call failed
+    #[error("contract call failed: {0}")]
+    Contract(#[from] alloy::contract::Error),
+
+    /// Waiting for a pending transaction failed
+    #[error("pending transaction failed: {0}")]
+    PendingTransaction(#[from] PendingTransactionError),
+
+    /// Signing a hash failed
+    #[error("signing failed: {0}")]
+    Signing(#[from] alloy::signers::Error),
+
+    /// A signature is malformed or cannot be recovered
+    #[error("invalid signature: {0}")]
+    InvalidSignature(#[from] Eip7702Error),
+
+    /// The transaction was mined but its execution reverted
+    #[error("transaction {tx_hash} reverted in block {block_number:?}")]
+    Reverted {
+        tx_hash: TxHash,
+        block_number: Option<u64>,
+    },
+
+    /// A batch was mined but did not initialize every delegated EOA
+    #[error("transaction {tx_hash} did not initialize {authorities:?}")]
+    NotInitialized { tx_hash: TxHash, authorities: Vec<Address> },
+
+    /// Execution of a call batch reverted before the transaction was mined
+    #[error("execution reverted: {0}")]
+    ExecutionReverted(Box<ExecutionRevert>),
+
+    /// The node returned an unexpected simulation response
+    #[error("simulation failed: {0}")]
+    Simulation(String),
+
+    /// The account holds contract code instead of a delegation designator
+    #[error("{0} is a contract, not an EOA")]
+    NotAnEoa(Address),
+
+    /// The account does not delegate to WalletCore
+    #[error("{0} does not delegate to WalletCore")]
+    NotDelegated(Address),
+
+    /// The delegate is not a known WalletCore deployment on the chain
+    #[error("{delegate} is not a known WalletCore deployment on chain {chain_id}; allow unknown delegates to proceed")]
+    UnknownDelegate { chain_id: u64, delegate: Address },
+
+    /// The authorization was not signed by the expected authority
+    #[error("authorization signed by {actual}, expected {expected}")]
+    AuthorizationMismatch { expected: Address, actual: Address },
+
+    /// A signed authorization failed verification
+    #[error("invalid authorization: {0}")]
+    InvalidAuthorization(Box<AuthorizationReport>),
+
+    /// Validation data is malformed or not signed by the wallet owner
+    #[error("invalid validation signature for {wallet}: {reason}")]
+    InvalidValidationSignature { wallet: Address, reason: String },
+
+    /// Calls were signed for a WalletCore nonce other than the wallet's current one
+    #[error("calls for {wallet} were signed for nonce {actual}, expected {expected}")]
+    WrongWalletNonce { wallet: Address, expected: U256, actual: U256 },
+
+    /// The locally computed validation hash differs from the on-chain one
+    #[error("validation hash mismatch for {wallet}: local {local} != on-chain {onchain}")]
+    ValidationHashMismatch {
+        wallet: Address,
+        local: FixedBytes<32>,
+        onchain: FixedBytes<32>,
+    },
+}
+
+/// Broad category of an [`Error`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Missing or invalid configuration or input, keys included
+    Config,
+    /// The node or a remote service could not be reached or answered with an error
+    Network,
+    /// Signing failed or a signature or authorization is invalid
+    Signature,
+    /// Execution reverted, before or after being mined
+    Reverted,
+    /// The account's code or delegation does not allow the operation
+    Delegation,
+}
+
+impl Error {
+    /// Get the category of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::MissingConfig(_)
+            | Error::InvalidConfig { .. }
+            | Error::InvalidKey { .. }
+            | Error::InvalidCall { .. }
+            | Error::InvalidManifest { .. }
+            | Error::ChainIdMismatch { .. }
+            | Error::BatchTooLarge { .. }
+            | Error::CannotReplace { .. }
+            | Error::FeeCapExceeded { .. }
+            | Error::MissingArtifact(_)
+            | Error::MissingContract { .. }
+            | Error::SessionPolicyViolation(_) => ErrorKind::Config,
+            Error::Rpc(_) | Error::Contract(_) if self.revert_data().is_some() => ErrorKind::Reverted,
+            Error::Io(_)
+            | Error::Anvil(_)
+            | Error::QueueClosed
+            | Error::ConfirmationTimeout { .. }
+            | Error::Rpc(_)
+            | Error::Contract(_)
+            | Error::PendingTransaction(_) => ErrorKind::Network,
+            Error::Signing(_)
+            | Error::InvalidSignature(_)
+            | Error::AuthorizationMismatch { .. }
+            | Error::InvalidAuthorization(_)
+            | Error::InvalidValidationSignature { .. }
+            | Error::WrongWalletNonce { .. } => ErrorKind::Signature,
+            Error::Reverted { .. }
+            | Error::NotInitialized { .. }
+            | Error::ExecutionReverted(_)
+            | Error::Simulation(_)
+            | Error::InsufficientBalance { .. } => ErrorKind::Reverted,
+            Error::NotAnEoa(_)
+            | Error::NotDelegated(_)
+            | Error::UnknownDelegate { .. }
+            | Error::ValidationHashMismatch { .. } => {
+                ErrorKind::Delegation
+            }
+        }
+    }
+
+    /// Get the raw revert data carried by an RPC or contract error
+    pub fn revert_data(&self) -> Option<Bytes> {
+        match self {
+            Error::Rpc(error) => error.as_error_resp().and_then(|payload| payload.as_revert_data()),
+            Error::Contract(error) => error.as_revert_data(),
+            _ => None,
+        }
+    }
+
+    /// Decode any revert data against the executed `calls` into [`Error::ExecutionReverted`]
+    pub fn decode_revert(self, calls: &[Call]) -> Error {
+        match self.revert_data() {
+            Some(data) => Error::ExecutionReverted(Box::new(ExecutionRevert::decode(&data, calls))),
+            None => self,
+        }
+    }
+}
+
+/// Result type of the core module
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,342 @@
+use alloy::{
+    eips::BlockNumberOrTag,
+    network::TransactionBuilder7702,
+    primitives::U256,
+    providers::{utils::eip1559_default_estimator, Provider},
+    rpc::types::TransactionRequest,
+    transports::RpcError,
+};
+
+use crate::core::{
+    builder::Eip7702Builder,
+    error::{Error, Result},
+    profile::{FeePolicy, FeeStrategy},
+    revert,
+    simulate::{authorization_list_unsupported, delegation_overrides},
+};
+
+/// Intrinsic gas charged for each entry of an authorization list (EIP-7702)
+///
+/// Nodes that accept the authorization list already count it in `eth_estimateGas`, so it is
+/// only added where the list is replaced by a state override: the fallback of
+/// [`Eip7702Builder::estimate_gas_limit`] and state override simulations.
+pub const PER_EMPTY_ACCOUNT_COST: u64 = 25_000;
+
+/// Reward percentile used by the `percentile` strategy when the policy sets none
+pub const DEFAULT_REWARD_PERCENTILE: u8 = 20;
+
+/// Blocks of fee history read by the `percentile` strategy when the policy sets none
+pub const DEFAULT_FEE_HISTORY_BLOCKS: u64 = 10;
+
+/// Fees chosen for a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    /// Base fee of the next block, unknown with the `fixed` strategy
+    pub base_fee_per_gas: Option<u128>,
+}
+
+/// Intrinsic gas of the authorization list of `tx`
+pub fn authorization_gas(tx: &TransactionRequest) -> u64 {
+    tx.authorization_list()
+        .map_or(0, |authorizations| authorizations.len() as u64 * PER_EMPTY_ACCOUNT_COST)
+}
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Choose fees with the strategy of the network profile
+    ///
+    /// Fees set explicitly in the profile take precedence over the strategy's.
+    pub async fn estimate_fees(&self) -> Result<FeeEstimate> {
+        let policy = self.config().fee_policy;
+
+        let estimate = match policy.strategy.unwrap_or_default() {
+            FeeStrategy::Fixed => FeeEstimate {
+                max_fee_per_gas: policy
+                    .max_fee_per_gas
+                    .ok_or(Error::MissingConfig("MAX_FEE_PER_GAS"))?
+                    .into(),
+                max_priority_fee_per_gas: policy
+                    .max_priority_fee_per_gas
+                    .ok_or(Error::MissingConfig("MAX_PRIORITY_FEE_PER_GAS"))?
+                    .into(),
+                base_fee_per_gas: None,
+            },
+            FeeStrategy::Percentile => {
+                let percentile = policy.reward_percentile.unwrap_or(DEFAULT_REWARD_PERCENTILE);
+                if percentile > 100 {
+                    return Err(Error::InvalidConfig {
+                        name: "FEE_REWARD_PERCENTILE",
+                        reason: format!("{} is not a percentile", percentile),
+                    });
+                }
+                let blocks = policy.fee_history_blocks.unwrap_or(DEFAULT_FEE_HISTORY_BLOCKS);
+
+                let history = self
+                    .provider()
+                    .get_fee_history(blocks, BlockNumberOrTag::Latest, &[f64::from(percentile)])
+                    .await?;
+                let base_fee = history
+                    .next_block_base_fee()
+                    .or_else(|| history.latest_block_base_fee())
+                    .ok_or(RpcError::UnsupportedFeature("eip1559"))?;
+                let estimate = eip1559_default_estimator(base_fee, &history.reward.unwrap_or_default());
+
+                FeeEstimate {
+                    max_fee_per_gas: policy
+                        .max_fee_per_gas
+                        .map_or(estimate.max_fee_per_gas, u128::from),
+                    max_priority_fee_per_gas: policy
+                        .max_priority_fee_per_gas
+                        .map_or(estimate.max_priority_fee_per_gas, u128::from),
+                    base_fee_per_gas: Some(base_fee),
+                }
+            }
+        };
+
+        Ok(FeeEstimate {
+            max_fee_per_gas: estimate.max_fee_per_gas.max(estimate.max_priority_fee_per_gas),
+            ..estimate
+        })
+    }
+
+    /// Estimate the gas limit of a transaction, including its authorization list
+    ///
+    /// When the node rejects the authorization list, the calls are estimated with the
+    /// delegations injected by a state override, and the intrinsic cost of each authorization
+    /// is added on top.
+    pub async fn estimate_gas_limit(&self, tx: &TransactionRequest) -> Result<u64> {
+        let calls = revert::batch_calls(tx);
+        let estimate = self.provider().estimate_gas(tx.clone()).await.map_err(Error::from);
+
+        match estimate {
+            // The node charged the authorization list itself
+            Ok(gas) => Ok(gas),
+            Err(error) if authorization_list_unsupported(&error) && authorization_gas(tx) > 0 => {
+                let overrides = delegation_overrides(tx)?;
+                let mut request = tx.clone();
+                request.authorization_list = None;
+                let gas = self
+                    .provider()
+                    .estimate_gas(request)
+                    .overrides(overrides)
+                    .await
+                    .map_err(|e| Error::from(e).decode_revert(&calls))?;
+                Ok(gas + authorization_gas(tx))
+            }
+            Err(error) => Err(error.decode_revert(&calls)),
+        }
+    }
+
+    /// Fill the gas limit and fees left unset in `tx`, then apply the profile's fee caps
+    pub async fn fill_gas_and_fees(&self, tx: &mut TransactionRequest) -> Result<()> {
+        if tx.gas.is_none() {
+            tx.gas = Some(self.estimate_gas_limit(tx).await?);
+        }
+
+        let mut base_fee = None;
+        if tx.gas_price.is_none() && (tx.max_fee_per_gas.is_none() || tx.max_priority_fee_per_gas.is_none()) {
+            let estimate = self.estimate_fees().await?;
+            let priority = *tx
+                .max_priority_fee_per_gas
+                .get_or_insert(estimate.max_priority_fee_per_gas);
+            tx.max_fee_per_gas.get_or_insert(estimate.max_fee_per_gas.max(priority));
+            base_fee = estimate.base_fee_per_gas;
+        }
+
+        cap_fees(tx, &self.config().fee_policy, base_fee)
+    }
+}
+
+/// Lower the max fee of `tx` to the policy's caps, failing if that leaves it below `needed`
+pub(crate) fn cap_fees(tx: &mut TransactionRequest, policy: &FeePolicy, needed: Option<u128>) -> Result<()> {
+    let per_gas_cap = policy.max_fee_per_gas_cap.map(u128::from);
+    let total_cost_cap = policy
+        .max_total_cost
+        .zip(tx.gas)
+        .map(|(max_total_cost, gas)| (max_total_cost / U256::from(gas.max(1))).saturating_to::<u128>());
+    let cap = match (per_gas_cap, total_cost_cap) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (cap, None) | (None, cap) => cap,
+    };
+    let Some(cap) = cap else {
+        return Ok(());
+    };
+
+    if let Some(needed) = needed
+        && cap < needed
+    {
+        return Err(Error::FeeCapExceeded {
+            max_fee_per_gas: cap,
+            needed,
+        });
+    }
+
+    if let Some(gas_price) = &mut tx.gas_price {
+        *gas_price = (*gas_price).min(cap);
+    }
+    if let Some(max_fee) = &mut tx.max_fee_per_gas {
+        *max_fee = (*max_fee).min(cap);
+        let max_fee = *max_fee;
+        if let Some(priority) = &mut tx.max_priority_fee_per_gas {
+            *priority = (*priority).min(max_fee);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{providers::ProviderBuilder, transports::mock::Asserter};
+    use serde_json::json;
+
+    use super::*;
+    use crate::core::config::Config;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    fn builder(fee_policy: FeePolicy, asserter: &Asserter) -> Eip7702Builder<impl Provider> {
+        let config = Config {
+            fee_policy,
+            ..Config::for_tests()
+        };
+        Eip7702Builder::with_provider(config, ProviderBuilder::new().on_mocked_client(asserter.clone()))
+    }
+
+    /// Fee history of three blocks whose next base fee is 10 gwei
+    fn push_fee_history(asserter: &Asserter) {
+        asserter.push_success(&json!({
+            "oldestBlock": "0x10",
+            "baseFeePerGas": ["0x1", "0x1", "0x1", format!("{:#x}", 10 * GWEI)],
+            "gasUsedRatio": [0.5, 0.5, 0.5],
+            "reward": [[format!("{:#x}", 2 * GWEI)], [format!("{:#x}", 4 * GWEI)], [format!("{:#x}", 3 * GWEI)]],
+        }));
+    }
+
+    fn eip1559_tx(gas: u64, max_fee: u128, priority: u128) -> TransactionRequest {
+        TransactionRequest {
+            gas: Some(gas),
+            max_fee_per_gas: Some(max_fee),
+            max_priority_fee_per_gas: Some(priority),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn percentile_tips_the_median_reward_over_twice_the_base_fee() {
+        let asserter = Asserter::new();
+        push_fee_history(&asserter);
+
+        let estimate = builder(FeePolicy::default(), &asserter).estimate_fees().await.unwrap();
+        assert_eq!(
+            estimate,
+            FeeEstimate {
+                max_fee_per_gas: 23 * GWEI,
+                max_priority_fee_per_gas: 3 * GWEI,
+                base_fee_per_gas: Some(10 * GWEI),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn percentile_keeps_fees_set_in_the_policy() {
+        let asserter = Asserter::new();
+        push_fee_history(&asserter);
+        let policy = FeePolicy {
+            max_priority_fee_per_gas: Some(GWEI as u64),
+            ..Default::default()
+        };
+
+        let estimate = builder(policy, &asserter).estimate_fees().await.unwrap();
+        assert_eq!(estimate.max_priority_fee_per_gas, GWEI);
+        assert_eq!(estimate.max_fee_per_gas, 23 * GWEI);
+    }
+
+    #[tokio::test]
+    async fn percentile_above_100_is_rejected() {
+        let policy = FeePolicy {
+            reward_percentile: Some(101),
+            ..Default::default()
+        };
+
+        let result = builder(policy, &Asserter::new()).estimate_fees().await;
+        assert!(matches!(
+            result,
+            Err(Error::InvalidConfig {
+                name: "FEE_REWARD_PERCENTILE",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn caps_max_fee_and_priority_fee() {
+        let policy = FeePolicy {
+            max_fee_per_gas_cap: Some(5 * GWEI as u64),
+            ..Default::default()
+        };
+        let mut tx = eip1559_tx(100_000, 20 * GWEI, 8 * GWEI);
+
+        cap_fees(&mut tx, &policy, None).unwrap();
+        assert_eq!(tx.max_fee_per_gas, Some(5 * GWEI));
+        assert_eq!(tx.max_priority_fee_per_gas, Some(5 * GWEI));
+    }
+
+    #[test]
+    fn caps_total_cost_by_gas_limit() {
+        let policy = FeePolicy {
+            max_fee_per_gas_cap: Some(50 * GWEI as u64),
+            // 0.001 ETH over 100k gas leaves 10 gwei per gas
+            max_total_cost: Some(U256::from(1_000_000 * GWEI)),
+            ..Default::default()
+        };
+        let mut tx = eip1559_tx(100_000, 20 * GWEI, 2 * GWEI);
+
+        cap_fees(&mut tx, &policy, Some(9 * GWEI)).unwrap();
+        assert_eq!(tx.max_fee_per_gas, Some(10 * GWEI));
+        assert_eq!(tx.max_priority_fee_per_gas, Some(2 * GWEI));
+    }
+
+    #[test]
+    fn total_cost_above_u128_does_not_cap() {
+        let policy = FeePolicy {
+            max_total_cost: Some(U256::MAX),
+            ..Default::default()
+        };
+        let mut tx = eip1559_tx(1, 20 * GWEI, 2 * GWEI);
+
+        cap_fees(&mut tx, &policy, None).unwrap();
+        assert_eq!(tx.max_fee_per_gas, Some(20 * GWEI));
+    }
+
+    #[test]
+    fn cap_below_needed_fee_fails() {
+        let policy = FeePolicy {
+            max_fee_per_gas_cap: Some(5 * GWEI as u64),
+            ..Default::default()
+        };
+        let mut tx = eip1559_tx(21_000, 20 * GWEI, 2 * GWEI);
+
+        let result = cap_fees(&mut tx, &policy, Some(6 * GWEI));
+        assert!(matches!(
+            result,
+            Err(Error::FeeCapExceeded { max_fee_per_gas, needed }) if max_fee_per_gas == 5 * GWEI && needed == 6 * GWEI
+        ));
+    }
+
+    #[test]
+    fn caps_legacy_gas_price() {
+        let policy = FeePolicy {
+            max_fee_per_gas_cap: Some(5 * GWEI as u64),
+            ..Default::default()
+        };
+        let mut tx = TransactionRequest {
+            gas: Some(21_000),
+            gas_price: Some(7 * GWEI),
+            ..Default::default()
+        };
+
+        cap_fees(&mut tx, &policy, None).unwrap();
+        assert_eq!(tx.gas_price, Some(5 * GWEI));
+    }
+}
@@ -0,0 +1,414 @@
+use std::{future::Future, io, sync::Arc, time::Duration};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+/// JSON-RPC 2.0 request
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcRequest {
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// JSON-RPC 2.0 error object
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+
+    /// Create an error without data
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Error for a method the service does not provide
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(Self::METHOD_NOT_FOUND, format!("method `{}` not found", method))
+    }
+
+    /// Error for parameters that cannot be decoded
+    pub fn invalid_params(reason: impl ToString) -> Self {
+        Self::new(Self::INVALID_PARAMS, reason.to_string())
+    }
+
+    /// Attach data to the error
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+/// Methods of a JSON-RPC service
+pub trait RpcHandler: Send + Sync + 'static {
+    /// Handle one call and return its result
+    fn handle(&self, method: &str, params: Value) -> impl Future<Output = Result<Value, RpcError>> + Send;
+}
+
+/// Decode the params of a call
+pub fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(RpcError::invalid_params)
+}
+
+/// Largest request body accepted, chunked bodies included
+pub const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Largest request line and header section accepted
+pub const MAX_HEADER_SIZE: usize = 16 * 1024;
+
+/// Time a client has to send a whole request, and to take the whole response
+pub const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Serve JSON-RPC 2.0 over HTTP `POST` requests accepted on `listener`
+///
+/// Each connection is handled on its own task, with keep-alive support. Connections that are
+/// idle or slower than [`IO_TIMEOUT`] are closed.
+pub async fn serve<H: RpcHandler>(listener: TcpListener, handler: Arc<H>) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, handler.as_ref()).await {
+                eprintln!("JSON-RPC connection failed: {}", e);
+            }
+        });
+    }
+}
+
+/// HTTP status of a request answered without running any call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    BadRequest,
+    MethodNotAllowed,
+    PayloadTooLarge,
+    HeaderTooLarge,
+    NotImplemented,
+}
+
+impl Status {
+    fn line(self) -> &'static str {
+        match self {
+            Status::Ok => "200 OK",
+            Status::BadRequest => "400 Bad Request",
+            Status::MethodNotAllowed => "405 Method Not Allowed",
+            Status::PayloadTooLarge => "413 Payload Too Large",
+            Status::HeaderTooLarge => "431 Request Header Fields Too Large",
+            Status::NotImplemented => "501 Not Implemented",
+        }
+    }
+}
+
+/// Why no request body could be read
+#[derive(Debug)]
+enum ReadError {
+    /// The client closed the connection between two requests
+    Closed,
+    /// The request is malformed or not acceptable; the connection is closed after answering
+    Rejected(Status, String),
+    Io(io::Error),
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::UnexpectedEof => ReadError::Closed,
+            _ => ReadError::Io(error),
+        }
+    }
+}
+
+/// Answer the requests of one HTTP connection until the client closes it
+async fn handle_connection<H: RpcHandler>(stream: TcpStream, handler: &H) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    loop {
+        let request = match timeout(IO_TIMEOUT, read_request(&mut reader)).await {
+            Ok(request) => request,
+            // Idle or too slow: drop the connection
+            Err(_) => return Ok(()),
+        };
+
+        let (status, response) = match request {
+            Ok(body) => (Status::Ok, dispatch(handler, &body).await),
+            Err(ReadError::Closed) => return Ok(()),
+            Err(ReadError::Io(e)) => return Err(e),
+            Err(ReadError::Rejected(status, message)) => (
+                status,
+                error_response(Value::Null, RpcError::new(RpcError::INVALID_REQUEST, message)),
+            ),
+        };
+
+        let body = serde_json::to_vec(&response)?;
+        let mut header = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n",
+            status.line(),
+            body.len()
+        );
+        if status == Status::MethodNotAllowed {
+            header.push_str("Allow: POST\r\n");
+        }
+        if status != Status::Ok {
+            header.push_str("Connection: close\r\n");
+        }
+        header.push_str("\r\n");
+
+        let stream = reader.get_mut();
+        let write = async {
+            stream.write_all(header.as_bytes()).await?;
+            stream.write_all(&body).await?;
+            stream.flush().await
+        };
+        match timeout(IO_TIMEOUT, write).await {
+            Ok(result) => result?,
+            Err(_) => return Ok(()),
+        }
+
+        // The rest of a rejected request cannot be framed
+        if status != Status::Ok {
+            return Ok(());
+        }
+    }
+}
+
+/// Read one `POST` request and return its body, with `Content-Length` or chunked framing
+async fn read_request<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Vec<u8>, ReadError> {
+    let mut header_budget = MAX_HEADER_SIZE;
+    let request_line = read_line(reader, &mut header_budget).await?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    loop {
+        let line = read_line(reader, &mut header_budget).await?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(rejected(Status::BadRequest, format!("malformed header `{}`", line)));
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            let length = value
+                .parse::<usize>()
+                .map_err(|_| rejected(Status::BadRequest, format!("invalid Content-Length `{}`", value)))?;
+            if content_length.is_some_and(|previous| previous != length) {
+                return Err(rejected(Status::BadRequest, "conflicting Content-Length headers"));
+            }
+            content_length = Some(length);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            if !value.eq_ignore_ascii_case("chunked") {
+                return Err(rejected(
+                    Status::NotImplemented,
+                    format!("unsupported Transfer-Encoding `{}`", value),
+                ));
+            }
+            chunked = true;
+        }
+    }
+
+    if !request_line.starts_with("POST ") {
+        return Err(rejected(Status::MethodNotAllowed, "expected a POST request"));
+    }
+
+    match (chunked, content_length) {
+        (true, Some(_)) => Err(rejected(
+            Status::BadRequest,
+            "both Transfer-Encoding and Content-Length are set",
+        )),
+        (true, None) => read_chunked_body(reader).await,
+        (false, Some(length)) if length > MAX_BODY_SIZE => Err(too_large()),
+        (false, length) => {
+            let mut body = vec![0; length.unwrap_or(0)];
+            reader.read_exact(&mut body).await?;
+            Ok(body)
+        }
+    }
+}
+
+/// Read a `Transfer-Encoding: chunked` body and its trailers
+async fn read_chunked_body<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Vec<u8>, ReadError> {
+    let mut body = Vec::new();
+    let mut line_budget = MAX_HEADER_SIZE;
+
+    loop {
+        let line = read_line(reader, &mut line_budget).await?;
+        let size = line.trim_end().split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| rejected(Status::BadRequest, format!("invalid chunk size `{}`", size)))?;
+
+        if size == 0 {
+            // Trailers end with an empty line
+            while !read_line(reader, &mut line_budget).await?.trim_end().is_empty() {}
+            return Ok(body);
+        }
+        if body.len().saturating_add(size) > MAX_BODY_SIZE {
+            return Err(too_large());
+        }
+
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..]).await?;
+        let mut end = [0; 2];
+        reader.read_exact(&mut end).await?;
+        if &end != b"\r\n" {
+            return Err(rejected(Status::BadRequest, "chunk is not followed by CRLF"));
+        }
+    }
+}
+
+/// Read a line, counting it against the bytes left in `budget`
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R, budget: &mut usize) -> Result<String, ReadError> {
+    let mut line = Vec::new();
+    let read = reader.take(*budget as u64).read_until(b'\n', &mut line).await?;
+    *budget -= read;
+    if !line.ends_with(b"\n") {
+        return Err(if read > 0 && *budget == 0 {
+            rejected(Status::HeaderTooLarge, "request headers are too large")
+        } else {
+            ReadError::Closed
+        });
+    }
+    String::from_utf8(line).map_err(|_| rejected(Status::BadRequest, "request headers are not UTF-8"))
+}
+
+fn rejected(status: Status, message: impl Into<String>) -> ReadError {
+    ReadError::Rejected(status, message.into())
+}
+
+fn too_large() -> ReadError {
+    rejected(
+        Status::PayloadTooLarge,
+        format!("request body is larger than {} bytes", MAX_BODY_SIZE),
+    )
+}
+
+/// Decode a request body and run the call, or a batch of calls
+async fn dispatch<H: RpcHandler>(handler: &H, body: &[u8]) -> Value {
+    let value: Value = match serde_json::from_slice(body) {
+        Ok(value) => value,
+        Err(e) => return error_response(Value::Null, RpcError::new(RpcError::PARSE_ERROR, e.to_string())),
+    };
+
+    match value {
+        Value::Array(requests) => {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses.push(call(handler, request).await);
+            }
+            Value::Array(responses)
+        }
+        request => call(handler, request).await,
+    }
+}
+
+/// Run a single call
+async fn call<H: RpcHandler>(handler: &H, request: Value) -> Value {
+    let request: RpcRequest = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => return error_response(Value::Null, RpcError::new(RpcError::INVALID_REQUEST, e.to_string())),
+    };
+
+    match handler.handle(&request.method, request.params).await {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }),
+        Err(error) => error_response(request.id, error),
+    }
+}
+
+/// Build an error response
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(request: &str) -> Result<Vec<u8>, ReadError> {
+        read_request(&mut request.as_bytes()).await
+    }
+
+    fn status(result: Result<Vec<u8>, ReadError>) -> Status {
+        match result {
+            Err(ReadError::Rejected(status, _)) => status,
+            other => panic!("expected a rejection, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_content_length_body() {
+        let body = read("POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}").await.unwrap();
+        assert_eq!(body, b"{}");
+    }
+
+    #[tokio::test]
+    async fn reads_chunked_body() {
+        let request = "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\n[{}\r\n1;ext=1\r\n]\r\n0\r\n\r\n";
+        assert_eq!(read(request).await.unwrap(), b"[{}]");
+    }
+
+    #[tokio::test]
+    async fn rejects_other_methods() {
+        assert_eq!(status(read("GET / HTTP/1.1\r\n\r\n").await), Status::MethodNotAllowed);
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_content_length() {
+        let request = "POST / HTTP/1.1\r\nContent-Length: twelve\r\n\r\n";
+        assert_eq!(status(read(request).await), Status::BadRequest);
+    }
+
+    #[tokio::test]
+    async fn rejects_large_bodies() {
+        let request = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_SIZE + 1);
+        assert_eq!(status(read(&request).await), Status::PayloadTooLarge);
+
+        let request = format!("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n", MAX_BODY_SIZE + 1);
+        assert_eq!(status(read(&request).await), Status::PayloadTooLarge);
+    }
+
+    #[tokio::test]
+    async fn rejects_large_headers() {
+        let request = format!("POST / HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(MAX_HEADER_SIZE));
+        assert_eq!(status(read(&request).await), Status::HeaderTooLarge);
+    }
+
+    #[tokio::test]
+    async fn rejects_ambiguous_framing() {
+        let request = "POST / HTTP/1.1\r\nContent-Length: 2\r\nTransfer-Encoding: chunked\r\n\r\n";
+        assert_eq!(status(read(request).await), Status::BadRequest);
+
+        let request = "POST / HTTP/1.1\r\nTransfer-Encoding: gzip\r\n\r\n";
+        assert_eq!(status(read(request).await), Status::NotImplemented);
+    }
+
+    #[tokio::test]
+    async fn truncated_request_closes_the_connection() {
+        assert!(matches!(read("").await, Err(ReadError::Closed)));
+        assert!(matches!(
+            read("POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\n{}").await,
+            Err(ReadError::Closed)
+        ));
+    }
+}
@@ -1,10 +1,53 @@
+pub mod audit;
 pub mod builder;
+pub mod calls;
 pub mod config;
 pub mod contracts;
+#[cfg(feature = "devnet")]
+pub mod devnet;
+pub mod error;
+pub mod fees;
+pub mod jsonrpc;
+pub mod nonce;
+pub mod onboarding;
+pub mod payouts;
+pub mod profile;
+pub mod queue;
+pub mod registry;
+pub mod relayer;
+pub mod remote_signer;
+pub mod replacement;
+pub mod revert;
+pub mod session;
+pub mod signer;
+pub mod simulate;
+pub mod typed_data;
 pub mod types;
+pub mod validators;
+pub mod verify;
 
 // Re-export main types for convenience
+pub use audit::{AttemptKind, AuditEntry, AuditEvent, AuditHook, AuditLog};
 pub use builder::Eip7702Builder;
+pub use calls::CallBuilder;
 pub use config::Config;
-pub use contracts::{Call, IWalletCore, IStorage, ERC20};
-pub use types::Addresses; 
\ No newline at end of file
+#[cfg(feature = "devnet")]
+pub use devnet::Devnet;
+pub use error::{Error, ErrorKind, Result};
+pub use fees::FeeEstimate;
+pub use contracts::{Call, IStorage, IValidator, IWalletCore, Session, ERC20};
+pub use nonce::NonceManager;
+pub use onboarding::{InitializeOutcome, InitializeStatus};
+pub use payouts::{ManifestRow, TransferReport, TransferRow, TransferStatus};
+pub use profile::{ConfigFile, FeePolicy, FeeStrategy, NetworkProfile, ReplacementPolicy};
+pub use queue::{QueuedTransaction, SubmissionQueue};
+pub use registry::WalletCoreDeployment;
+pub use relayer::{RelayIntent, RelayStatus, Relayer, RelayerClient, RelayerInfo};
+pub use remote_signer::{MockRemoteSigner, RemoteSigner};
+pub use revert::{ExecutionRevert, RevertReason};
+pub use session::{SessionKey, SessionKeyValidatorArgs, SessionPolicy};
+pub use signer::{PayloadSigner, WalletSigner};
+pub use simulate::{BalanceDelta, SimulationMode, SimulationResult};
+pub use types::{Addresses, Delegation};
+pub use validators::{EcdsaValidatorArgs, InstalledValidator, ValidatorArgs};
+pub use verify::{AuthorizationIssue, AuthorizationReport, ExpectedAuthorization}; 
\ No newline at end of file
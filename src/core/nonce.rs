@@ -0,0 +1,222 @@
+use std::collections::BTreeSet;
+
+use alloy::{primitives::Address, providers::Provider};
+use tokio::sync::Mutex;
+
+use crate::core::error::{Error, Result};
+
+/// Hands out sequential transaction nonces for one sender
+///
+/// Nonces are handed out locally so that concurrent submissions never race for the same
+/// one. A nonce that was handed out but never broadcast is reused by the next transaction,
+/// filling the gap it would otherwise leave.
+#[derive(Debug)]
+pub struct NonceManager {
+    address: Address,
+    state: Mutex<NonceState>,
+}
+
+#[derive(Debug, Default)]
+struct NonceState {
+    /// Lowest nonce never handed out, `None` until synced from chain
+    next: Option<u64>,
+    /// Nonces handed out and not yet mined
+    in_flight: BTreeSet<u64>,
+    /// Nonces handed out but never broadcast
+    released: BTreeSet<u64>,
+}
+
+impl NonceManager {
+    /// Create a manager for `address`; nonces are synced from chain on first use
+    pub fn new(address: Address) -> Self {
+        NonceManager {
+            address,
+            state: Mutex::new(NonceState::default()),
+        }
+    }
+
+    /// Get the sender whose nonces are managed
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Hand out the next nonce, reusing released nonces first
+    pub async fn next<P: Provider>(&self, provider: &P) -> Result<u64> {
+        let mut state = self.state.lock().await;
+
+        if let Some(nonce) = state.released.pop_first() {
+            state.in_flight.insert(nonce);
+            return Ok(nonce);
+        }
+
+        let mut nonce = match state.next {
+            Some(next) => next,
+            None => self.sync(&mut state, provider).await?,
+        };
+        // Transactions queued behind a gap keep their nonces across a re-sync
+        while state.in_flight.contains(&nonce) {
+            nonce += 1;
+        }
+        state.next = Some(nonce + 1);
+        state.in_flight.insert(nonce);
+        Ok(nonce)
+    }
+
+    /// Give back a nonce whose transaction was never broadcast
+    pub async fn release(&self, nonce: u64) {
+        let mut state = self.state.lock().await;
+        if !state.in_flight.remove(&nonce) {
+            return;
+        }
+
+        if state.next == Some(nonce + 1) {
+            // Rewind over the released nonces at the tail
+            let mut next = nonce;
+            while next > 0 && state.released.remove(&(next - 1)) {
+                next -= 1;
+            }
+            state.next = Some(next);
+        } else {
+            state.released.insert(nonce);
+        }
+    }
+
+    /// Mark the transaction using `nonce` as mined
+    pub async fn confirm(&self, nonce: u64) {
+        self.state.lock().await.in_flight.remove(&nonce);
+    }
+
+    /// Get the nonces handed out and not yet mined
+    pub async fn in_flight(&self) -> Vec<u64> {
+        self.state.lock().await.in_flight.iter().copied().collect()
+    }
+
+    /// Get the unused nonces below a broadcast transaction; that transaction cannot be mined
+    /// until they are used
+    pub async fn gaps(&self) -> Vec<u64> {
+        let state = self.state.lock().await;
+        match state.in_flight.last() {
+            Some(highest) => state.released.range(..highest).copied().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Drop the local state and read the nonces from chain, returning the next nonce
+    ///
+    /// Call this after a node rejects a nonce or a transaction is dropped from the mempool.
+    pub async fn resync<P: Provider>(&self, provider: &P) -> Result<u64> {
+        let mut state = self.state.lock().await;
+        self.sync(&mut state, provider).await
+    }
+
+    async fn sync<P: Provider>(&self, state: &mut NonceState, provider: &P) -> Result<u64> {
+        let mined = provider.get_transaction_count(self.address).latest().await?;
+        let pending = provider.get_transaction_count(self.address).pending().await?;
+
+        // Nonces below `pending` are in the mempool and the node holds none at `pending`
+        // itself; higher ones may still be queued behind that gap
+        let next = pending.max(mined);
+        state.in_flight.retain(|nonce| *nonce >= mined && *nonce != next);
+        state.released.clear();
+        state.next = Some(next);
+        Ok(next)
+    }
+}
+
+/// Whether a node rejected a transaction because of its nonce
+pub fn is_nonce_error(error: &Error) -> bool {
+    let Error::Rpc(error) = error else {
+        return false;
+    };
+    error.as_error_resp().is_some_and(|payload| {
+        let message = payload.message.to_lowercase();
+        message.contains("nonce")
+            || message.contains("already known")
+            || message.contains("replacement transaction underpriced")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{primitives::U64, providers::ProviderBuilder, transports::mock::Asserter};
+
+    use super::*;
+
+    /// Provider whose account has `mined` mined transactions and `pending` counting the mempool
+    fn provider(mined: u64, pending: u64) -> (impl Provider, Asserter) {
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(mined));
+        asserter.push_success(&U64::from(pending));
+        (ProviderBuilder::new().on_mocked_client(asserter.clone()), asserter)
+    }
+
+    async fn hand_out(nonces: &NonceManager, provider: &impl Provider, count: usize) -> Vec<u64> {
+        let mut handed_out = Vec::new();
+        for _ in 0..count {
+            handed_out.push(nonces.next(provider).await.unwrap());
+        }
+        handed_out
+    }
+
+    #[tokio::test]
+    async fn hands_out_sequential_nonces_from_chain() {
+        let (provider, _) = provider(3, 5);
+        let nonces = NonceManager::new(Address::ZERO);
+
+        assert_eq!(hand_out(&nonces, &provider, 3).await, [5, 6, 7]);
+        assert_eq!(nonces.in_flight().await, [5, 6, 7]);
+    }
+
+    #[tokio::test]
+    async fn released_nonce_is_a_gap_until_reused() {
+        let (provider, _) = provider(0, 0);
+        let nonces = NonceManager::new(Address::ZERO);
+        hand_out(&nonces, &provider, 3).await;
+
+        nonces.release(1).await;
+        assert_eq!(nonces.gaps().await, [1]);
+        assert_eq!(nonces.next(&provider).await.unwrap(), 1);
+        assert!(nonces.gaps().await.is_empty());
+        assert_eq!(nonces.next(&provider).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn releasing_the_tail_rewinds_over_released_nonces() {
+        let (provider, _) = provider(0, 0);
+        let nonces = NonceManager::new(Address::ZERO);
+        hand_out(&nonces, &provider, 3).await;
+
+        nonces.release(1).await;
+        nonces.release(2).await;
+        assert!(nonces.gaps().await.is_empty());
+        assert_eq!(nonces.in_flight().await, [0]);
+        assert_eq!(hand_out(&nonces, &provider, 2).await, [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn confirmed_and_unknown_nonces_are_not_released() {
+        let (provider, _) = provider(0, 0);
+        let nonces = NonceManager::new(Address::ZERO);
+        hand_out(&nonces, &provider, 2).await;
+
+        nonces.confirm(0).await;
+        nonces.release(0).await;
+        nonces.release(9).await;
+        assert_eq!(nonces.in_flight().await, [1]);
+        assert_eq!(nonces.next(&provider).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn resync_keeps_nonces_queued_behind_a_gap() {
+        let (provider, asserter) = provider(0, 0);
+        let nonces = NonceManager::new(Address::ZERO);
+        hand_out(&nonces, &provider, 4).await;
+
+        // 0 was mined, 1 was dropped, 2 and 3 wait behind the gap
+        asserter.push_success(&U64::from(1));
+        asserter.push_success(&U64::from(1));
+        assert_eq!(nonces.resync(&provider).await.unwrap(), 1);
+        assert_eq!(nonces.in_flight().await, [2, 3]);
+        assert_eq!(hand_out(&nonces, &provider, 2).await, [1, 4]);
+    }
+}
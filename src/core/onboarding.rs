@@ -0,0 +1,214 @@
+use alloy::{
+    eips::eip7702::SignedAuthorization,
+    network::{TransactionBuilder, TransactionBuilder7702, TxSigner},
+    primitives::{Address, PrimitiveSignature as Signature},
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+    signers::Signer,
+    sol_types::SolCall,
+};
+
+use crate::core::{
+    builder::Eip7702Builder,
+    contracts::{IMulticall3, IWalletCore},
+    error::{Error, Result},
+    signer::PayloadSigner,
+    types::{Addresses, Delegation},
+};
+
+/// How `initialize()` ended for one EOA of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitializeStatus {
+    /// The batch created the storage of the EOA
+    Initialized,
+    /// The EOA already had its storage before the batch
+    AlreadyInitialized,
+    /// The EOA has no storage after the batch
+    Failed,
+}
+
+/// Result of delegating and initializing one EOA in a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitializeOutcome {
+    /// The EOA that signed the authorization
+    pub authority: Address,
+    /// How `initialize()` ended for this EOA
+    pub status: InitializeStatus,
+    /// Storage of the EOA, unless it failed
+    pub storage_address: Option<Address>,
+}
+
+impl InitializeOutcome {
+    /// Whether the EOA has its storage after the batch, created now or before
+    pub fn is_initialized(&self) -> bool {
+        self.status != InitializeStatus::Failed
+    }
+}
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Create WalletCore authorizations for several EOAs submitted together by `sender`
+    pub async fn create_authorizations<A: PayloadSigner>(
+        &self,
+        authorizers: &[A],
+        sender: Address,
+    ) -> Result<Vec<SignedAuthorization>> {
+        let mut authorizations = Vec::with_capacity(authorizers.len());
+        for authorizer in authorizers {
+            authorizations.push(self.create_authorization(authorizer, sender).await?);
+        }
+        Ok(authorizations)
+    }
+
+    /// Build a transaction that delegates many EOAs and calls `initialize()` on each of them.
+    ///
+    /// The calls go through Multicall3 with `allowFailure` set, so one failing
+    /// EOA does not revert the whole batch.
+    pub fn build_initialize_batch_transaction(
+        &self,
+        authorizations: Vec<SignedAuthorization>,
+    ) -> Result<TransactionRequest> {
+        let calls = authorizations
+            .iter()
+            .map(|authorization| {
+                Ok(IMulticall3::Call3 {
+                    target: authorization.recover_authority()?,
+                    allowFailure: true,
+                    callData: IWalletCore::initializeCall {}.abi_encode().into(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let calldata = IMulticall3::aggregate3Call { calls }.abi_encode();
+
+        Ok(TransactionRequest::default()
+            .with_to(Addresses::MULTICALL3)
+            .with_authorization_list(authorizations)
+            .with_input(calldata))
+    }
+
+    /// Delegate and initialize many EOAs in one transaction paid by `sender`
+    ///
+    /// Fails before sending when Multicall3 is not deployed on the chain, since a call to an
+    /// address without code succeeds. Once the batch is mined, the outcome of every EOA is
+    /// returned with the receipt, including EOAs whose `initialize()` failed.
+    pub async fn initialize_batch<A, S>(
+        &self,
+        authorizers: &[A],
+        sender: &S,
+    ) -> Result<(TransactionReceipt, Vec<InitializeOutcome>)>
+    where
+        A: PayloadSigner,
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        if self.provider().get_code_at(Addresses::MULTICALL3).await?.is_empty() {
+            return Err(Error::MissingContract {
+                name: "Multicall3",
+                address: Addresses::MULTICALL3,
+            });
+        }
+
+        let authorizations = self.create_authorizations(authorizers, sender.address()).await?;
+        let tx = self.build_initialize_batch_transaction(authorizations)?;
+        let receipt = self.send_transaction(tx, sender).await?;
+
+        let authorities: Vec<Address> = authorizers.iter().map(Signer::address).collect();
+        let mut outcomes = decode_initialize_outcomes(&receipt, &authorities);
+        for outcome in outcomes.iter_mut().filter(|outcome| !outcome.is_initialized()) {
+            // No `StorageCreated` event: the storage may predate the batch
+            if let Delegation::WalletCore(_) = self.get_delegation(outcome.authority).await?
+                && let Some(storage_address) = self.get_initialized_storage(outcome.authority).await?
+            {
+                outcome.status = InitializeStatus::AlreadyInitialized;
+                outcome.storage_address = Some(storage_address);
+            }
+        }
+        Ok((receipt, outcomes))
+    }
+}
+
+/// Decode per-authority initialization results from the receipt of a batch
+pub fn decode_initialize_outcomes(receipt: &TransactionReceipt, authorities: &[Address]) -> Vec<InitializeOutcome> {
+    authorities
+        .iter()
+        .map(|authority| {
+            let storage_address = receipt
+                .inner
+                .logs()
+                .iter()
+                .filter(|log| log.address() == *authority)
+                .find_map(|log| log.log_decode::<IWalletCore::StorageCreated>().ok())
+                .map(|event| event.inner.data.storageAddress);
+
+            InitializeOutcome {
+                authority: *authority,
+                status: match storage_address {
+                    Some(_) => InitializeStatus::Initialized,
+                    None => InitializeStatus::Failed,
+                },
+                storage_address,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{primitives::address, sol_types::SolEvent};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn outcomes_come_from_storage_created_events() {
+        let alice = address!("0x1000000000000000000000000000000000000001");
+        let bob = address!("0x1000000000000000000000000000000000000002");
+        let storage = address!("0x5000000000000000000000000000000000000005");
+
+        let receipt: TransactionReceipt = serde_json::from_value(json!({
+            "type": "0x4",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x30000",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "logs": [{
+                "address": alice,
+                "topics": [IWalletCore::StorageCreated::SIGNATURE_HASH],
+                "data": IWalletCore::StorageCreated { storageAddress: storage }.encode_data(),
+                "blockHash": format!("0x{}", "11".repeat(32)),
+                "blockNumber": "0x1",
+                "transactionHash": format!("0x{}", "22".repeat(32)),
+                "transactionIndex": "0x0",
+                "logIndex": "0x0",
+                "removed": false
+            }],
+            "transactionHash": format!("0x{}", "22".repeat(32)),
+            "transactionIndex": "0x0",
+            "blockHash": format!("0x{}", "11".repeat(32)),
+            "blockNumber": "0x1",
+            "gasUsed": "0x30000",
+            "effectiveGasPrice": "0x1",
+            "from": address!("0x4000000000000000000000000000000000000004"),
+            "to": Addresses::MULTICALL3,
+            "contractAddress": null
+        }))
+        .unwrap();
+
+        let outcomes = decode_initialize_outcomes(&receipt, &[alice, bob]);
+
+        assert_eq!(
+            outcomes,
+            vec![
+                InitializeOutcome {
+                    authority: alice,
+                    status: InitializeStatus::Initialized,
+                    storage_address: Some(storage),
+                },
+                InitializeOutcome {
+                    authority: bob,
+                    status: InitializeStatus::Failed,
+                    storage_address: None,
+                },
+            ]
+        );
+        assert!(outcomes[0].is_initialized());
+        assert!(!outcomes[1].is_initialized());
+    }
+}
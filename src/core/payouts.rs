@@ -0,0 +1,396 @@
+use std::{collections::BTreeMap, path::Path};
+
+use alloy::{
+    network::{TransactionBuilder, TxSigner},
+    primitives::{Address, PrimitiveSignature as Signature, TxHash, U256, utils::parse_units},
+    providers::Provider,
+    rpc::types::{
+        TransactionReceipt, TransactionRequest,
+        state::{StateOverride, StateOverridesBuilder},
+    },
+    sol_types::SolCall,
+};
+use serde::Deserialize;
+
+use crate::core::{
+    builder::Eip7702Builder,
+    calls::CallBuilder,
+    contracts::{Call, ERC20, IWalletCore},
+    error::{Error, Result},
+    signer::PayloadSigner,
+    types::delegation_code,
+};
+
+/// Row of a payout manifest, as written in the CSV or JSON file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ManifestRow {
+    /// Token symbol from the network profile, or token address
+    pub token: String,
+    pub recipient: Address,
+    /// Amount in token units, e.g. `1.5`
+    pub amount: String,
+}
+
+/// Resolved ERC20 transfer of a payout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferRow {
+    /// 1-based position of the row in the manifest
+    pub row: usize,
+    pub token: Address,
+    pub recipient: Address,
+    /// Amount in base units
+    pub amount: U256,
+}
+
+/// What happened to a row of a payout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferStatus {
+    /// The transfer was mined; `log_index` points at its `Transfer` event
+    Sent {
+        tx_hash: TxHash,
+        block_number: Option<u64>,
+        log_index: Option<u64>,
+    },
+    /// The batch holding the row failed
+    Failed(String),
+    /// The row was not sent because an earlier batch failed
+    NotSent,
+}
+
+/// Outcome of one row of a payout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferReport {
+    pub transfer: TransferRow,
+    pub status: TransferStatus,
+}
+
+/// Read a payout manifest with `token,recipient,amount` rows
+///
+/// `.json` files hold an array of objects with the same fields; anything else is read as CSV
+/// with a header line.
+pub fn read_manifest(path: &Path) -> Result<Vec<ManifestRow>> {
+    let invalid = |reason: String| Error::InvalidConfig {
+        name: "payout manifest",
+        reason: format!("{}: {}", path.display(), reason),
+    };
+
+    if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+        let contents = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        return serde_json::from_str(&contents).map_err(|e| invalid(e.to_string()));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(|e| invalid(e.to_string()))?;
+    reader
+        .deserialize()
+        .enumerate()
+        .map(|(index, row)| {
+            row.map_err(|e| Error::InvalidManifest {
+                row: index + 1,
+                reason: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Resolve token symbols and scale amounts by each token's decimals
+    pub async fn resolve_transfers(&self, rows: &[ManifestRow]) -> Result<Vec<TransferRow>> {
+        let mut decimals: BTreeMap<Address, u8> = BTreeMap::new();
+        let mut transfers = Vec::with_capacity(rows.len());
+
+        for (index, row) in rows.iter().enumerate() {
+            let invalid = |reason: String| Error::InvalidManifest { row: index + 1, reason };
+
+            let token = self.config().token(&row.token).map_err(|e| invalid(e.to_string()))?;
+            let token_decimals = match decimals.get(&token) {
+                Some(token_decimals) => *token_decimals,
+                None => {
+                    let token_decimals = self.get_token_decimals(token).await?;
+                    decimals.insert(token, token_decimals);
+                    token_decimals
+                }
+            };
+            let amount = parse_units(&row.amount, token_decimals)
+                .map_err(|e| invalid(format!("invalid amount `{}`: {}", row.amount, e)))?;
+            if amount.is_negative() {
+                return Err(invalid(format!("negative amount `{}`", row.amount)));
+            }
+            let amount = amount.get_absolute();
+
+            transfers.push(TransferRow {
+                row: index + 1,
+                token,
+                recipient: row.recipient,
+                amount,
+            });
+        }
+
+        Ok(transfers)
+    }
+
+    /// Check that `wallet` holds enough of every token to pay all transfers
+    pub async fn check_transfer_balances(&self, wallet: Address, transfers: &[TransferRow]) -> Result<()> {
+        let mut needed: BTreeMap<Address, U256> = BTreeMap::new();
+        for transfer in transfers {
+            let total = needed.entry(transfer.token).or_default();
+            *total = total.saturating_add(transfer.amount);
+        }
+
+        for (token, needed) in needed {
+            let available = self.get_balance_of(token, wallet).await?;
+            if available < needed {
+                return Err(Error::InsufficientBalance {
+                    token,
+                    account: wallet,
+                    needed,
+                    available,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pay `transfers` from `authority`'s wallet in as few `executeWithValidator` batches as
+    /// fit under `max_gas_per_batch`; `sender` submits every batch and pays gas.
+    ///
+    /// Batches are sent one after the other. If one fails, its rows are reported as failed
+    /// and the remaining rows are not sent.
+    pub async fn send_transfers<A, S>(
+        &self,
+        authority: &A,
+        sender: &S,
+        transfers: &[TransferRow],
+        max_gas_per_batch: u64,
+    ) -> Result<Vec<TransferReport>>
+    where
+        A: PayloadSigner,
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let wallet = authority.address();
+        self.check_transfer_balances(wallet, transfers).await?;
+
+        let mut reports = Vec::with_capacity(transfers.len());
+        let mut start = 0;
+        while start < transfers.len() {
+            let result = self
+                .fit_transfer_batch(authority, sender.address(), &transfers[start..], max_gas_per_batch)
+                .await;
+            // Rows that could not be fitted into a batch were all part of the attempt
+            let (end, tx) = match result {
+                Ok((len, tx)) => (start + len, tx),
+                Err(e) => {
+                    reports.extend(failed(&transfers[start..], &e));
+                    start = transfers.len();
+                    break;
+                }
+            };
+
+            let batch = &transfers[start..end];
+            start = end;
+            match self.send_transaction(tx, sender).await {
+                Ok(receipt) => reports.extend(report_batch(&receipt, wallet, batch)),
+                Err(e) => {
+                    reports.extend(failed(batch, &e));
+                    break;
+                }
+            }
+        }
+
+        reports.extend(transfers[start..].iter().map(|transfer| TransferReport {
+            transfer: *transfer,
+            status: TransferStatus::NotSent,
+        }));
+        Ok(reports)
+    }
+
+    /// Find how many of `transfers` fit in one batch under `max_gas`, returning the signed
+    /// transaction for them
+    ///
+    /// Batches are sized with unsigned `executeFromSelf` estimates run against the wallet with its
+    /// delegation injected, then signed once. Only when the signed transaction's validation
+    /// overhead pushes it over `max_gas` is the batch shrunk by that overhead and signed again.
+    async fn fit_transfer_batch<A: PayloadSigner>(
+        &self,
+        authority: &A,
+        sender: Address,
+        transfers: &[TransferRow],
+        max_gas: u64,
+    ) -> Result<(usize, TransactionRequest)> {
+        let wallet = authority.address();
+        let overrides: StateOverride = StateOverridesBuilder::default()
+            .with_code(wallet, delegation_code(self.config().wallet_core_address))
+            .into();
+
+        let mut len = transfers.len();
+        let mut budget = max_gas;
+        loop {
+            let (fitted, unsigned_gas) = self
+                .fit_unsigned_batch(wallet, &transfers[..len], budget, &overrides)
+                .await?;
+            len = fitted;
+
+            let calls = transfer_calls(&transfers[..len]);
+            let tx = self
+                .build_signed_execute_transaction(authority, sender, &calls)
+                .await?;
+            let gas = self
+                .provider()
+                .estimate_gas(tx.clone().with_from(sender))
+                .await
+                .map_err(|e| Error::from(e).decode_revert(&calls))?;
+
+            if gas <= max_gas {
+                return Ok((len, tx));
+            }
+            let overhead = gas.saturating_sub(unsigned_gas);
+            if len == 1 || overhead >= max_gas {
+                return Err(Error::BatchTooLarge { gas, max_gas });
+            }
+            budget = max_gas - overhead;
+        }
+    }
+
+    /// Find how many of `transfers` fit under `max_gas` when executed by the wallet itself,
+    /// returning that count and its gas
+    async fn fit_unsigned_batch(
+        &self,
+        wallet: Address,
+        transfers: &[TransferRow],
+        max_gas: u64,
+        overrides: &StateOverride,
+    ) -> Result<(usize, u64)> {
+        let mut len = transfers.len();
+        loop {
+            let calls = transfer_calls(&transfers[..len]);
+            let tx = TransactionRequest::default()
+                .with_from(wallet)
+                .with_to(wallet)
+                .with_input(IWalletCore::executeFromSelfCall { calls: calls.clone() }.abi_encode());
+            let gas = self
+                .provider()
+                .estimate_gas(tx)
+                .overrides(overrides.clone())
+                .await
+                .map_err(|e| Error::from(e).decode_revert(&calls))?;
+
+            if gas <= max_gas {
+                return Ok((len, gas));
+            }
+            if len == 1 {
+                return Err(Error::BatchTooLarge { gas, max_gas });
+            }
+
+            // Shrink in proportion to the overshoot, always dropping at least one row
+            let scaled = (len as u128 * max_gas as u128 / gas as u128) as usize;
+            len = scaled.clamp(1, len - 1);
+        }
+    }
+}
+
+/// Build the ERC20 transfer calls of `transfers`
+fn transfer_calls(transfers: &[TransferRow]) -> Vec<Call> {
+    transfers
+        .iter()
+        .fold(CallBuilder::new(), |calls, transfer| {
+            calls.erc20_transfer(transfer.token, transfer.recipient, transfer.amount)
+        })
+        .build()
+}
+
+/// Report every transfer of a batch as failed
+fn failed(batch: &[TransferRow], error: &Error) -> Vec<TransferReport> {
+    let reason = error.to_string();
+    batch
+        .iter()
+        .map(|transfer| TransferReport {
+            transfer: *transfer,
+            status: TransferStatus::Failed(reason.clone()),
+        })
+        .collect()
+}
+
+/// Tie each transfer of a mined batch to the `Transfer` event it emitted
+fn report_batch(receipt: &TransactionReceipt, wallet: Address, batch: &[TransferRow]) -> Vec<TransferReport> {
+    let mut events: Vec<(Address, ERC20::Transfer, Option<u64>)> = receipt
+        .inner
+        .logs()
+        .iter()
+        .filter_map(|log| {
+            let event = log.log_decode::<ERC20::Transfer>().ok()?;
+            Some((log.address(), event.inner.data, log.log_index))
+        })
+        .collect();
+
+    batch
+        .iter()
+        .map(|transfer| {
+            // Events are consumed in order so that identical rows map to distinct logs
+            let position = events.iter().position(|(token, event, _)| {
+                *token == transfer.token
+                    && event.from == wallet
+                    && event.to == transfer.recipient
+                    && event.value == transfer.amount
+            });
+            let log_index = position.and_then(|position| events.remove(position).2);
+
+            TransferReport {
+                transfer: *transfer,
+                status: TransferStatus::Sent {
+                    tx_hash: receipt.transaction_hash,
+                    block_number: receipt.block_number,
+                    log_index,
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Bytes, U256},
+        providers::ProviderBuilder,
+        sol_types::SolValue,
+        transports::mock::Asserter,
+    };
+
+    use super::*;
+    use crate::core::config::Config;
+
+    fn row(amount: &str) -> ManifestRow {
+        ManifestRow {
+            token: Address::repeat_byte(0x20).to_string(),
+            recipient: Address::repeat_byte(0x33),
+            amount: amount.to_string(),
+        }
+    }
+
+    fn builder(asserter: &Asserter) -> Eip7702Builder<impl Provider> {
+        // `decimals()` of the token
+        asserter.push_success(&Bytes::from(U256::from(6).abi_encode()));
+        Eip7702Builder::with_provider(Config::for_tests(), ProviderBuilder::new().on_mocked_client(asserter.clone()))
+    }
+
+    #[tokio::test]
+    async fn scales_amounts_by_decimals() {
+        let asserter = Asserter::new();
+        let transfers = builder(&asserter)
+            .resolve_transfers(&[row("1.5"), row("2")])
+            .await
+            .unwrap();
+        assert_eq!(transfers[0].amount, U256::from(1_500_000));
+        assert_eq!(transfers[1].amount, U256::from(2_000_000));
+        assert_eq!(transfers[1].row, 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_negative_amounts() {
+        let asserter = Asserter::new();
+        let result = builder(&asserter).resolve_transfers(&[row("1"), row("-5")]).await;
+        assert!(matches!(result, Err(Error::InvalidManifest { row: 2, .. })));
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use alloy::{
+    primitives::{Address, U256, address},
+    transports::http::reqwest::Url,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::core::error::{Error, Result};
+
+/// LINK token on Sepolia, the default token of the built-in Sepolia profile
+const SEPOLIA_LINK: Address = address!("0x779877A7B0D9E8603169DdbD7836e478b4624789");
+
+/// How the builder chooses the fees of a transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeStrategy {
+    /// Priority fee at a percentile of the rewards of recent blocks from `eth_feeHistory`,
+    /// max fee of twice the next base fee plus the priority fee
+    #[default]
+    Percentile,
+    /// `max_fee_per_gas` and `max_priority_fee_per_gas` from the policy, without any RPC call
+    Fixed,
+}
+
+impl FromStr for FeeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "percentile" => Ok(FeeStrategy::Percentile),
+            "fixed" => Ok(FeeStrategy::Fixed),
+            _ => Err(format!("unknown fee strategy `{}`, expected `percentile` or `fixed`", s)),
+        }
+    }
+}
+
+/// Fees of the transactions sent by the builder
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeePolicy {
+    /// How fees are chosen, `percentile` if unset
+    pub strategy: Option<FeeStrategy>,
+    /// Maximum fee per gas in wei; chosen by the strategy if unset
+    pub max_fee_per_gas: Option<u64>,
+    /// Maximum priority fee per gas in wei; chosen by the strategy if unset
+    pub max_priority_fee_per_gas: Option<u64>,
+    /// Reward percentile of the `percentile` strategy, 20 if unset
+    pub reward_percentile: Option<u8>,
+    /// Blocks of fee history read by the `percentile` strategy, 10 if unset
+    pub fee_history_blocks: Option<u64>,
+    /// Highest max fee per gas ever used, in wei
+    pub max_fee_per_gas_cap: Option<u64>,
+    /// Highest `gas_limit * max_fee_per_gas` of a transaction, in wei
+    #[serde(default, deserialize_with = "deserialize_wei")]
+    pub max_total_cost: Option<U256>,
+}
+
+/// Deserialize an amount of wei written as an integer or as a decimal or `0x` string; TOML
+/// integers stop at `i64::MAX` wei, about 9.2 ETH
+fn deserialize_wei<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<U256>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Wei {
+        Integer(u64),
+        String(U256),
+    }
+
+    Ok(Option::<Wei>::deserialize(deserializer)?.map(|wei| match wei {
+        Wei::Integer(wei) => U256::from(wei),
+        Wei::String(wei) => wei,
+    }))
+}
+
+impl FeePolicy {
+    /// Overlay the fields set in `other` on top of this policy
+    pub fn merge(self, other: FeePolicy) -> FeePolicy {
+        FeePolicy {
+            strategy: other.strategy.or(self.strategy),
+            max_fee_per_gas: other.max_fee_per_gas.or(self.max_fee_per_gas),
+            max_priority_fee_per_gas: other.max_priority_fee_per_gas.or(self.max_priority_fee_per_gas),
+            reward_percentile: other.reward_percentile.or(self.reward_percentile),
+            fee_history_blocks: other.fee_history_blocks.or(self.fee_history_blocks),
+            max_fee_per_gas_cap: other.max_fee_per_gas_cap.or(self.max_fee_per_gas_cap),
+            max_total_cost: other.max_total_cost.or(self.max_total_cost),
+        }
+    }
+}
+
+/// Fee increase of a replacement transaction when the profile does not set one; nodes
+/// require at least 10%
+pub const DEFAULT_FEE_BUMP_PERCENT: u64 = 15;
+
+/// Number of replacements of a stuck transaction when the profile does not set one
+pub const DEFAULT_MAX_FEE_BUMPS: u32 = 3;
+
+/// Replacement of transactions that are not mined in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplacementPolicy {
+    /// Seconds to wait for a receipt before replacing the transaction; wait forever if unset
+    pub confirmation_timeout_secs: Option<u64>,
+    /// Increase of both fees of each replacement, in percent
+    pub fee_bump_percent: Option<u64>,
+    /// Replacements sent before giving up
+    pub max_fee_bumps: Option<u32>,
+}
+
+impl ReplacementPolicy {
+    /// Get the confirmation timeout, `None` to wait forever
+    pub fn confirmation_timeout(&self) -> Option<Duration> {
+        self.confirmation_timeout_secs.map(Duration::from_secs)
+    }
+
+    /// Get the fee increase of each replacement, in percent
+    pub fn fee_bump_percent(&self) -> u64 {
+        self.fee_bump_percent.unwrap_or(DEFAULT_FEE_BUMP_PERCENT)
+    }
+
+    /// Get the number of replacements sent before giving up
+    pub fn max_fee_bumps(&self) -> u32 {
+        self.max_fee_bumps.unwrap_or(DEFAULT_MAX_FEE_BUMPS)
+    }
+}
+
+/// Settings of a named network
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkProfile {
+    pub rpc_url: Option<Url>,
+    pub chain_id: Option<u64>,
+    /// WalletCore delegate, taken from the registry if unset
+    pub wallet_core_address: Option<Address>,
+    /// Validator passed to `executeWithValidator`, the built-in ECDSA validator if unset
+    pub validator_address: Option<Address>,
+    /// Known tokens by symbol
+    pub tokens: BTreeMap<String, Address>,
+    /// Symbol of the token used by default
+    pub default_token: Option<String>,
+    pub fees: FeePolicy,
+    pub replacement: ReplacementPolicy,
+    /// JSON lines file recording every transaction attempt
+    pub audit_log: Option<PathBuf>,
+    /// Allow delegates that are not in the registry
+    pub allow_unknown_delegate: Option<bool>,
+}
+
+impl NetworkProfile {
+    /// Get a built-in profile: `mainnet`, `sepolia`, `holesky` or `anvil`
+    pub fn builtin(name: &str) -> Option<NetworkProfile> {
+        let profile = match name {
+            "mainnet" => NetworkProfile {
+                chain_id: Some(1),
+                ..Default::default()
+            },
+            "sepolia" => NetworkProfile {
+                chain_id: Some(11155111),
+                tokens: BTreeMap::from([("LINK".to_string(), SEPOLIA_LINK)]),
+                default_token: Some("LINK".to_string()),
+                ..Default::default()
+            },
+            "holesky" => NetworkProfile {
+                chain_id: Some(17000),
+                ..Default::default()
+            },
+            "anvil" => NetworkProfile {
+                rpc_url: Some(Url::parse("http://127.0.0.1:8545").expect("valid URL")),
+                chain_id: Some(31337),
+                ..Default::default()
+            },
+            _ => return None,
+        };
+
+        Some(profile)
+    }
+
+    /// Overlay the fields set in `other` on top of this profile
+    pub fn merge(self, other: NetworkProfile) -> NetworkProfile {
+        let mut tokens = self.tokens;
+        tokens.extend(other.tokens);
+
+        NetworkProfile {
+            rpc_url: other.rpc_url.or(self.rpc_url),
+            chain_id: other.chain_id.or(self.chain_id),
+            wallet_core_address: other.wallet_core_address.or(self.wallet_core_address),
+            validator_address: other.validator_address.or(self.validator_address),
+            tokens,
+            default_token: other.default_token.or(self.default_token),
+            fees: self.fees.merge(other.fees),
+            replacement: ReplacementPolicy {
+                confirmation_timeout_secs: other
+                    .replacement
+                    .confirmation_timeout_secs
+                    .or(self.replacement.confirmation_timeout_secs),
+                fee_bump_percent: other.replacement.fee_bump_percent.or(self.replacement.fee_bump_percent),
+                max_fee_bumps: other.replacement.max_fee_bumps.or(self.replacement.max_fee_bumps),
+            },
+            audit_log: other.audit_log.or(self.audit_log),
+            allow_unknown_delegate: other.allow_unknown_delegate.or(self.allow_unknown_delegate),
+        }
+    }
+
+    /// Address of the default token, if any
+    pub fn default_token_address(&self) -> Option<Address> {
+        self.default_token
+            .as_ref()
+            .and_then(|symbol| self.tokens.get(symbol))
+            .copied()
+    }
+}
+
+/// Contents of a TOML or JSON config file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigFile {
+    /// Network used when none is selected explicitly
+    pub default_network: Option<String>,
+    /// Network profiles by name
+    pub networks: BTreeMap<String, NetworkProfile>,
+}
+
+impl ConfigFile {
+    /// Load a config file, parsed as JSON for `.json` files and as TOML otherwise
+    pub fn load(path: &Path) -> Result<ConfigFile> {
+        let invalid = |reason: String| Error::InvalidConfig {
+            name: "config file",
+            reason: format!("{}: {}", path.display(), reason),
+        };
+
+        let contents = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&contents).map_err(|e| invalid(e.to_string())),
+            _ => toml::from_str(&contents).map_err(|e| invalid(e.to_string())),
+        }
+    }
+
+    /// Resolve a network profile, layering the file's settings over the built-in profile
+    pub fn profile(&self, name: &str) -> Result<NetworkProfile> {
+        let builtin = NetworkProfile::builtin(name);
+        let custom = self.networks.get(name).cloned();
+
+        match (builtin, custom) {
+            (Some(builtin), Some(custom)) => Ok(builtin.merge(custom)),
+            (Some(profile), None) | (None, Some(profile)) => Ok(profile),
+            (None, None) => Err(Error::InvalidConfig {
+                name: "network",
+                reason: format!("unknown network `{}`", name),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anvil_profile_defaults_to_local_node() {
+        let anvil = NetworkProfile::builtin("anvil").unwrap();
+        assert_eq!(anvil.rpc_url.unwrap().as_str(), "http://127.0.0.1:8545/");
+        assert_eq!(anvil.chain_id, Some(31337));
+    }
+
+    #[test]
+    fn max_total_cost_accepts_integers_and_strings() {
+        let cost = |toml: &str| toml::from_str::<FeePolicy>(toml).unwrap().max_total_cost;
+
+        assert_eq!(cost(""), None);
+        assert_eq!(cost("max_total_cost = 1000"), Some(U256::from(1000)));
+        assert_eq!(
+            cost("max_total_cost = \"100000000000000000000\""),
+            Some(U256::from(100_000_000_000_000_000_000u128))
+        );
+        assert_eq!(cost("max_total_cost = \"0x3e8\""), Some(U256::from(1000)));
+        assert!(toml::from_str::<FeePolicy>("max_total_cost = -1").is_err());
+    }
+}
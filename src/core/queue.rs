@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use alloy::{
+    consensus::SignableTransaction,
+    network::{TransactionBuilder, TxSigner},
+    primitives::{Address, PrimitiveSignature as Signature, TxHash},
+    rpc::types::{TransactionReceipt, TransactionRequest},
+};
+use async_trait::async_trait;
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+use crate::core::{
+    audit::AttemptKind,
+    builder::Eip7702Builder,
+    error::{Error, Result},
+    nonce::{NonceManager, is_nonce_error},
+};
+
+/// Default number of transactions that may wait in a [`SubmissionQueue`]
+pub const DEFAULT_QUEUE_CAPACITY: usize = 64;
+
+/// Transaction broadcast by a [`SubmissionQueue`]
+#[derive(Debug, Clone)]
+pub struct QueuedTransaction {
+    pub nonce: u64,
+    pub tx_hash: TxHash,
+    /// Transaction as broadcast, with its gas and fees, sent again with higher fees if it gets stuck
+    pub tx: TransactionRequest,
+}
+
+/// Signer of a [`SubmissionQueue`], whatever its type
+#[derive(Clone)]
+struct QueueSigner(Arc<dyn TxSigner<Signature> + Send + Sync>);
+
+#[async_trait]
+impl TxSigner<Signature> for QueueSigner {
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    async fn sign_transaction(&self, tx: &mut dyn SignableTransaction<Signature>) -> alloy::signers::Result<Signature> {
+        self.0.sign_transaction(tx).await
+    }
+}
+
+/// Transaction waiting in the queue
+struct Submission {
+    tx: TransactionRequest,
+    reply: oneshot::Sender<Result<QueuedTransaction>>,
+}
+
+/// Bounded queue broadcasting transactions from one signer, one at a time
+///
+/// Every transaction gets its nonce from a [`NonceManager`], so any number of tasks can
+/// submit concurrently. Submitters wait while the queue is full.
+pub struct SubmissionQueue {
+    builder: Arc<Eip7702Builder>,
+    signer: QueueSigner,
+    nonces: Arc<NonceManager>,
+    submissions: mpsc::Sender<Submission>,
+    task: JoinHandle<()>,
+}
+
+impl SubmissionQueue {
+    /// Start a queue broadcasting from `signer`, holding at most `capacity` waiting transactions
+    pub fn spawn<S>(builder: Arc<Eip7702Builder>, signer: S, capacity: usize) -> Self
+    where
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let signer = QueueSigner(Arc::new(signer));
+        let nonces = Arc::new(NonceManager::new(signer.address()));
+        let (submissions, receiver) = mpsc::channel(capacity);
+        let task = tokio::spawn(run(builder.clone(), signer.clone(), nonces.clone(), receiver));
+
+        SubmissionQueue {
+            builder,
+            signer,
+            nonces,
+            submissions,
+            task,
+        }
+    }
+
+    /// Get the nonce manager of the queue's signer
+    pub fn nonces(&self) -> &NonceManager {
+        &self.nonces
+    }
+
+    /// Queue a transaction and wait until it is broadcast
+    pub async fn submit(&self, tx: TransactionRequest) -> Result<QueuedTransaction> {
+        let (reply, receiver) = oneshot::channel();
+        self.submissions
+            .send(Submission { tx, reply })
+            .await
+            .map_err(|_| Error::QueueClosed)?;
+        receiver.await.map_err(|_| Error::QueueClosed)?
+    }
+
+    /// Wait for a broadcast transaction to be mined
+    ///
+    /// Like [`Eip7702Builder::speed_up_transaction`], the transaction is sent again with higher
+    /// fees each time the replacement policy's confirmation timeout expires, and the wait fails
+    /// once the replacements are used up. The nonces are re-synced from chain if the transaction
+    /// cannot be followed, e.g. because it was dropped from the mempool.
+    pub async fn wait(&self, queued: QueuedTransaction) -> Result<TransactionReceipt> {
+        let confirmed = self
+            .builder
+            .confirm_transaction(queued.tx, &self.signer, vec![queued.tx_hash], AttemptKind::FeeBump)
+            .await;
+        match confirmed {
+            Ok(receipt) => {
+                self.nonces.confirm(queued.nonce).await;
+                Ok(receipt)
+            }
+            Err(e) => {
+                self.nonces.resync(self.builder.provider()).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Queue a transaction and wait for its receipt, like [`Eip7702Builder::send_transaction`]
+    pub async fn send(&self, tx: TransactionRequest) -> Result<TransactionReceipt> {
+        let queued = self.submit(tx).await?;
+        let receipt = self.wait(queued).await?;
+        if !receipt.status() {
+            return Err(Error::Reverted {
+                tx_hash: receipt.transaction_hash,
+                block_number: receipt.block_number,
+            });
+        }
+
+        Ok(receipt)
+    }
+}
+
+impl Drop for SubmissionQueue {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Broadcast queued transactions in order until every sender is dropped
+async fn run(
+    builder: Arc<Eip7702Builder>,
+    signer: QueueSigner,
+    nonces: Arc<NonceManager>,
+    mut submissions: mpsc::Receiver<Submission>,
+) {
+    while let Some(Submission { tx, reply }) = submissions.recv().await {
+        let result = broadcast(&builder, &signer, &nonces, tx).await;
+        // The submitter may have given up waiting
+        let _ = reply.send(result);
+    }
+}
+
+/// Broadcast one transaction with the next nonce of the signer, recorded in the audit log
+async fn broadcast(
+    builder: &Eip7702Builder,
+    signer: &QueueSigner,
+    nonces: &NonceManager,
+    tx: TransactionRequest,
+) -> Result<QueuedTransaction> {
+    let nonce = nonces.next(builder.provider()).await?;
+
+    let submitted = async {
+        let tx = builder.prepare_transaction(tx.with_nonce(nonce), signer.address()).await?;
+        let tx_hash = builder.submit_attempt(&tx, signer, AttemptKind::Original, None).await?;
+        Ok(QueuedTransaction { nonce, tx_hash, tx })
+    };
+    match submitted.await {
+        Ok(queued) => Ok(queued),
+        Err(e) => {
+            nonces.release(nonce).await;
+            if is_nonce_error(&e) {
+                nonces.resync(builder.provider()).await?;
+            }
+            Err(e)
+        }
+    }
+}
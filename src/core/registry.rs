@@ -0,0 +1,45 @@
+use alloy::primitives::{Address, address};
+
+use crate::core::types::Addresses;
+
+/// A known WalletCore deployment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalletCoreDeployment {
+    pub chain_id: u64,
+    /// WalletCore delegate contract
+    pub wallet_core: Address,
+    /// ECDSA validator accepted by this deployment
+    pub ecdsa_validator: Address,
+    /// Version of WalletCore and its main storage implementation
+    pub version: &'static str,
+}
+
+/// Known WalletCore deployments, newest last for each chain
+pub const DEPLOYMENTS: &[WalletCoreDeployment] = &[WalletCoreDeployment {
+    chain_id: 11155111,
+    wallet_core: address!("0x80296FF8D1ED46f8e3C7992664D13B833504c2Bb"),
+    ecdsa_validator: Addresses::ECDSA_VALIDATOR,
+    version: "1.0.0",
+}];
+
+/// Get every known deployment on a chain
+pub fn deployments(chain_id: u64) -> impl Iterator<Item = &'static WalletCoreDeployment> {
+    DEPLOYMENTS
+        .iter()
+        .filter(move |deployment| deployment.chain_id == chain_id)
+}
+
+/// Get the newest known deployment on a chain
+pub fn latest(chain_id: u64) -> Option<&'static WalletCoreDeployment> {
+    deployments(chain_id).last()
+}
+
+/// Find the deployment of a WalletCore address on a chain
+pub fn find(chain_id: u64, wallet_core: Address) -> Option<&'static WalletCoreDeployment> {
+    deployments(chain_id).find(|deployment| deployment.wallet_core == wallet_core)
+}
+
+/// Whether `delegate` is a known WalletCore deployment on a chain
+pub fn is_known_delegate(chain_id: u64, delegate: Address) -> bool {
+    find(chain_id, delegate).is_some()
+}
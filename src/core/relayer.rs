@@ -0,0 +1,696 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use alloy::{
+    eips::eip7702::SignedAuthorization,
+    network::TxSigner,
+    primitives::{Address, B256, Bytes, PrimitiveSignature as Signature, TxHash, U256},
+    providers::Provider,
+    rpc::{
+        client::RpcClient,
+        types::{TransactionRequest, state::StateOverridesBuilder},
+    },
+    transports::http::reqwest::Url,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+
+use crate::core::{
+    builder::Eip7702Builder,
+    contracts::{Call, IWalletCore},
+    error::{Error, ErrorKind, Result},
+    jsonrpc::{self, RpcError, RpcHandler},
+    nonce::NonceManager,
+    queue::SubmissionQueue,
+    signer::PayloadSigner,
+    types::{AuthorizationChain, Delegation, delegation_code},
+    verify::{ExpectedAuthorization, inspect_authorization},
+};
+
+/// Method returning the relayer's [`RelayerInfo`]
+pub const INFO_METHOD: &str = "relayer_info";
+
+/// Method accepting a [`RelayIntent`] and returning its tracking id
+pub const SUBMIT_METHOD: &str = "relayer_submit";
+
+/// Method returning the [`RelayStatus`] of a tracking id
+pub const STATUS_METHOD: &str = "relayer_status";
+
+/// JSON-RPC error code of intents whose simulation reverts, as used by Ethereum nodes
+pub const EXECUTION_REVERTED: i64 = 3;
+
+/// Number of mined or failed intents whose status a [`Relayer`] keeps, the oldest being forgotten first
+pub const MAX_FINISHED_RELAYS: usize = 1024;
+
+/// Call of a [`RelayIntent`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntentCall {
+    pub target: Address,
+    #[serde(default)]
+    pub value: U256,
+    #[serde(default)]
+    pub data: Bytes,
+}
+
+impl From<&Call> for IntentCall {
+    fn from(call: &Call) -> Self {
+        IntentCall {
+            target: call.target,
+            value: call.value,
+            data: call.data.clone(),
+        }
+    }
+}
+
+impl From<&IntentCall> for Call {
+    fn from(call: &IntentCall) -> Self {
+        Call {
+            target: call.target,
+            value: call.value,
+            data: call.data.clone(),
+        }
+    }
+}
+
+/// Calls signed off-chain by a wallet owner, for a relayer to submit and pay gas
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayIntent {
+    /// Delegation of the wallet to WalletCore, signed with the relayer as sender
+    pub authorization: SignedAuthorization,
+    pub calls: Vec<IntentCall>,
+    /// WalletCore nonce the calls were signed for
+    pub nonce: U256,
+    /// Signature of the validation hash of `calls` at `nonce`
+    pub signature: Bytes,
+}
+
+impl RelayIntent {
+    /// Get the calls of the intent as WalletCore calls
+    pub fn calls(&self) -> Vec<Call> {
+        self.calls.iter().map(Call::from).collect()
+    }
+}
+
+/// Intent that passed every check, ready to be submitted
+#[derive(Debug, Clone)]
+pub struct VerifiedIntent {
+    /// Wallet whose calls are executed
+    pub wallet: Address,
+    pub nonce: U256,
+    /// Validation hash of the calls, also used as tracking id
+    pub validation_hash: B256,
+    pub tx: TransactionRequest,
+}
+
+/// Progress of a relayed intent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum RelayStatus {
+    /// Accepted and waiting to be broadcast
+    Pending,
+    /// Broadcast and waiting to be mined
+    Submitted { tx_hash: TxHash },
+    /// Mined and executed successfully
+    Mined {
+        tx_hash: TxHash,
+        block_number: Option<u64>,
+        gas_used: u64,
+    },
+    /// Broadcasting failed or the transaction reverted on-chain
+    Failed { tx_hash: Option<TxHash>, error: String },
+}
+
+impl RelayStatus {
+    /// Whether the status can no longer change
+    pub fn is_final(&self) -> bool {
+        matches!(self, RelayStatus::Mined { .. } | RelayStatus::Failed { .. })
+    }
+}
+
+/// What a client needs to know to sign intents for a relayer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayerInfo {
+    /// Account submitting the transactions, the `sender` of authorizations
+    pub address: Address,
+    pub chain_id: u64,
+    /// Delegate that authorizations must point at
+    pub wallet_core: Address,
+    /// Validator the calls are executed with
+    pub validator: Address,
+}
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Sign `calls` for `authority`'s wallet as an intent that `relayer` will submit
+    pub async fn sign_intent<A: PayloadSigner>(
+        &self,
+        authority: &A,
+        relayer: Address,
+        calls: &[Call],
+    ) -> Result<RelayIntent> {
+        let wallet = authority.address();
+        let authorization = self.create_authorization(authority, relayer).await?;
+        let nonce = self.get_wallet_nonce(wallet).await?;
+        let validation_hash = self.get_validation_hash(wallet, nonce, calls).await?;
+        let signature = self
+            .sign_validation_data(authority, wallet, nonce, calls, &validation_hash)
+            .await?;
+
+        Ok(RelayIntent {
+            authorization,
+            calls: calls.iter().map(IntentCall::from).collect(),
+            nonce,
+            signature,
+        })
+    }
+
+    /// Check an intent that `relayer` would submit and build its transaction
+    ///
+    /// The authorization must delegate the wallet to the configured WalletCore, the calls must
+    /// be signed by the wallet for its current nonce, and the transaction must succeed in simulation.
+    pub async fn verify_intent(&self, intent: &RelayIntent, relayer: Address) -> Result<VerifiedIntent> {
+        let wallet = intent.authorization.recover_authority()?;
+        let chain_id = self.chain_id().await?;
+        let expected = ExpectedAuthorization {
+            authority: wallet,
+            delegate: self.config().wallet_core_address,
+            chain_id,
+            sender: relayer,
+            allow_any_chain: self.config().authorization_chain == AuthorizationChain::Any,
+        };
+
+        let delegation = self.get_delegation(wallet).await?;
+        match delegation {
+            // The authorization is left out of the transaction, so its nonce does not matter
+            Delegation::WalletCore(_) => {
+                let report = inspect_authorization(&intent.authorization, &expected);
+                if !report.is_valid() {
+                    return Err(Error::InvalidAuthorization(Box::new(report)));
+                }
+                let nonce = self.get_wallet_nonce(wallet).await?;
+                if nonce != intent.nonce {
+                    return Err(Error::WrongWalletNonce {
+                        wallet,
+                        expected: nonce,
+                        actual: intent.nonce,
+                    });
+                }
+            }
+            _ => {
+                self.ensure_valid_authorization(&intent.authorization, &expected)
+                    .await?;
+            }
+        }
+
+        let calls = intent.calls();
+        let validation_hash = self
+            .intent_validation_hash(wallet, delegation, intent.nonce, &calls)
+            .await?;
+        let invalid = |reason: String| Error::InvalidValidationSignature { wallet, reason };
+        let signature = Signature::try_from(intent.signature.as_ref()).map_err(|e| invalid(e.to_string()))?;
+        let signer = signature
+            .recover_address_from_prehash(&validation_hash)
+            .map_err(|e| invalid(e.to_string()))?;
+        if signer != wallet {
+            return Err(invalid(format!("signed by {}", signer)));
+        }
+
+        let tx = self
+            .build_execute_with_validator_transaction(
+                wallet,
+                intent.authorization.clone(),
+                &calls,
+                intent.signature.clone(),
+            )
+            .await?;
+
+        let simulation = self.simulate(&tx, relayer).await?;
+        if !simulation.success {
+            return Err(match simulation.revert {
+                Some(revert) => Error::ExecutionReverted(Box::new(revert)),
+                None => Error::Simulation("transaction reverted without data".to_string()),
+            });
+        }
+
+        Ok(VerifiedIntent {
+            wallet,
+            nonce: intent.nonce,
+            validation_hash,
+            tx,
+        })
+    }
+
+    /// Get the validation hash WalletCore computes for an intent
+    ///
+    /// A wallet that is not delegated yet is given WalletCore's code through a state override,
+    /// as the authorization carried by the intent would do.
+    async fn intent_validation_hash(
+        &self,
+        wallet: Address,
+        delegation: Delegation,
+        nonce: U256,
+        calls: &[Call],
+    ) -> Result<B256> {
+        if let Delegation::WalletCore(_) = delegation {
+            return self.get_validation_hash(wallet, nonce, calls).await;
+        }
+
+        let overrides = StateOverridesBuilder::default()
+            .with_code(wallet, delegation_code(self.config().wallet_core_address))
+            .build();
+        let wallet_core = IWalletCore::new(wallet, self.provider());
+        Ok(wallet_core
+            .getValidationTypedHash(nonce, calls.to_vec())
+            .state(overrides)
+            .call()
+            .await?
+            ._0)
+    }
+}
+
+/// Tracked intent
+struct Relay {
+    wallet: Address,
+    nonce: U256,
+    status: RelayStatus,
+}
+
+/// Tracked intents, forgetting the oldest finished ones beyond [`MAX_FINISHED_RELAYS`]
+#[derive(Default)]
+struct Relays {
+    by_id: HashMap<B256, Relay>,
+    /// Ids of the finished intents, oldest first
+    finished: VecDeque<B256>,
+}
+
+impl Relays {
+    fn insert(&mut self, id: B256, relay: Relay) {
+        // A failed intent submitted again is no longer finished
+        self.finished.retain(|finished| *finished != id);
+        self.by_id.insert(id, relay);
+    }
+
+    fn set_status(&mut self, id: B256, status: RelayStatus) {
+        let Some(relay) = self.by_id.get_mut(&id) else {
+            return;
+        };
+        let finished = status.is_final() && !relay.status.is_final();
+        relay.status = status;
+        if !finished {
+            return;
+        }
+
+        self.finished.push_back(id);
+        while self.finished.len() > MAX_FINISHED_RELAYS {
+            if let Some(oldest) = self.finished.pop_front() {
+                self.by_id.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Service that checks intents, submits them from its own signer and tracks their status
+pub struct Relayer {
+    builder: Arc<Eip7702Builder>,
+    address: Address,
+    queue: SubmissionQueue,
+    relays: Mutex<Relays>,
+}
+
+impl Relayer {
+    /// Create a relayer paying gas from `signer`, with at most `queue_capacity` intents waiting
+    /// to be broadcast
+    pub fn new<S>(builder: Eip7702Builder, signer: S, queue_capacity: usize) -> Self
+    where
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let builder = Arc::new(builder);
+        let address = signer.address();
+        let queue = SubmissionQueue::spawn(builder.clone(), signer, queue_capacity);
+        Relayer {
+            builder,
+            address,
+            queue,
+            relays: Mutex::new(Relays::default()),
+        }
+    }
+
+    /// Get the account submitting the transactions
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Describe the relayer to clients
+    pub async fn info(&self) -> Result<RelayerInfo> {
+        let config = self.builder.config();
+        Ok(RelayerInfo {
+            address: self.address,
+            chain_id: self.builder.chain_id().await?,
+            wallet_core: config.wallet_core_address,
+            validator: config.validator_address,
+        })
+    }
+
+    /// Verify an intent and submit it in the background, returning its tracking id
+    ///
+    /// Submitting the same intent again returns the same id unless it failed. Another intent
+    /// for a wallet nonce that is still in flight is rejected.
+    pub async fn submit(self: &Arc<Self>, intent: RelayIntent) -> Result<B256> {
+        let verified = self.builder.verify_intent(&intent, self.address).await?;
+        let id = verified.validation_hash;
+
+        {
+            let mut relays = self.relays.lock().expect("relays lock poisoned");
+            if let Some(relay) = relays.by_id.get(&id)
+                && !matches!(relay.status, RelayStatus::Failed { .. })
+            {
+                return Ok(id);
+            }
+            let in_flight = relays
+                .by_id
+                .values()
+                .any(|relay| relay.wallet == verified.wallet && relay.nonce == verified.nonce && !relay.status.is_final());
+            if in_flight {
+                return Err(Error::WrongWalletNonce {
+                    wallet: verified.wallet,
+                    expected: verified.nonce + U256::from(1),
+                    actual: verified.nonce,
+                });
+            }
+            relays.insert(
+                id,
+                Relay {
+                    wallet: verified.wallet,
+                    nonce: verified.nonce,
+                    status: RelayStatus::Pending,
+                },
+            );
+        }
+
+        let relayer = self.clone();
+        tokio::spawn(async move { relayer.broadcast(id, verified.tx).await });
+        Ok(id)
+    }
+
+    /// Get the status of an intent, `None` if the id is unknown or among the oldest finished intents
+    pub fn status(&self, id: B256) -> Option<RelayStatus> {
+        let relays = self.relays.lock().expect("relays lock poisoned");
+        relays.by_id.get(&id).map(|relay| relay.status.clone())
+    }
+
+    /// Get the nonce manager of the relayer's signer
+    pub fn nonces(&self) -> &NonceManager {
+        self.queue.nonces()
+    }
+
+    /// Serve the relayer's JSON-RPC methods on `listener`
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> std::io::Result<()> {
+        jsonrpc::serve(listener, Arc::new(RelayService(self))).await
+    }
+
+    /// Queue a verified transaction and follow it until it is mined
+    async fn broadcast(&self, id: B256, tx: TransactionRequest) {
+        let queued = match self.queue.submit(tx).await {
+            Ok(queued) => queued,
+            Err(e) => {
+                self.set_status(
+                    id,
+                    RelayStatus::Failed {
+                        tx_hash: None,
+                        error: e.to_string(),
+                    },
+                );
+                return;
+            }
+        };
+
+        let tx_hash = queued.tx_hash;
+        self.set_status(id, RelayStatus::Submitted { tx_hash });
+
+        let status = match self.queue.wait(queued).await {
+            // A replacement with higher fees may have been mined instead
+            Ok(receipt) if receipt.status() => RelayStatus::Mined {
+                tx_hash: receipt.transaction_hash,
+                block_number: receipt.block_number,
+                gas_used: receipt.gas_used,
+            },
+            Ok(receipt) => RelayStatus::Failed {
+                tx_hash: Some(receipt.transaction_hash),
+                error: Error::Reverted {
+                    tx_hash: receipt.transaction_hash,
+                    block_number: receipt.block_number,
+                }
+                .to_string(),
+            },
+            Err(e) => RelayStatus::Failed {
+                tx_hash: Some(tx_hash),
+                error: e.to_string(),
+            },
+        };
+        self.set_status(id, status);
+    }
+
+    fn set_status(&self, id: B256, status: RelayStatus) {
+        let mut relays = self.relays.lock().expect("relays lock poisoned");
+        relays.set_status(id, status);
+    }
+}
+
+/// JSON-RPC methods of a [`Relayer`]
+struct RelayService(Arc<Relayer>);
+
+impl RpcHandler for RelayService {
+    async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let relayer = &self.0;
+        match method {
+            INFO_METHOD => {
+                let info = relayer.info().await.map_err(rpc_error)?;
+                Ok(serde_json::to_value(info).expect("relayer info serializes"))
+            }
+            SUBMIT_METHOD => {
+                let (intent,): (RelayIntent,) = jsonrpc::params(params)?;
+                let id = relayer.submit(intent).await.map_err(rpc_error)?;
+                Ok(json!(id))
+            }
+            STATUS_METHOD => {
+                let (id,): (B256,) = jsonrpc::params(params)?;
+                let status = relayer
+                    .status(id)
+                    .ok_or_else(|| RpcError::invalid_params(format!("unknown relay id {}", id)))?;
+                Ok(serde_json::to_value(status).expect("relay status serializes"))
+            }
+            _ => Err(RpcError::method_not_found(method)),
+        }
+    }
+}
+
+/// Map a rejected intent to a JSON-RPC error
+fn rpc_error(error: Error) -> RpcError {
+    match (&error, error.kind()) {
+        (Error::ExecutionReverted(revert), _) => RpcError::new(EXECUTION_REVERTED, error.to_string()).with_data(json!({
+            "callIndex": revert.call_index,
+            "target": revert.target,
+            "reason": revert.reason.to_string(),
+        })),
+        (_, ErrorKind::Reverted) => RpcError::new(EXECUTION_REVERTED, error.to_string()),
+        (_, ErrorKind::Network) => RpcError::new(RpcError::INTERNAL_ERROR, error.to_string()),
+        _ => RpcError::invalid_params(error),
+    }
+}
+
+/// Client of a relayer's JSON-RPC methods
+#[derive(Clone)]
+pub struct RelayerClient {
+    client: RpcClient,
+}
+
+impl RelayerClient {
+    /// Create a client for the relayer at `url`
+    pub fn new(url: Url) -> Self {
+        RelayerClient {
+            client: RpcClient::new_http(url),
+        }
+    }
+
+    /// Get the relayer's address and deployment
+    pub async fn info(&self) -> Result<RelayerInfo> {
+        Ok(self.client.request_noparams(INFO_METHOD).await?)
+    }
+
+    /// Submit an intent and get its tracking id
+    pub async fn submit(&self, intent: &RelayIntent) -> Result<B256> {
+        Ok(self.client.request(SUBMIT_METHOD, (intent,)).await?)
+    }
+
+    /// Get the status of a submitted intent
+    pub async fn status(&self, id: B256) -> Result<RelayStatus> {
+        Ok(self.client.request(STATUS_METHOD, (id,)).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        eips::eip7702::Authorization,
+        primitives::U64,
+        providers::ProviderBuilder,
+        signers::{SignerSync, local::PrivateKeySigner},
+        sol_types::SolValue,
+        transports::mock::Asserter,
+    };
+
+    use super::*;
+    use crate::core::config::Config;
+
+    const CHAIN_ID: u64 = 31337;
+
+    fn builder(asserter: &Asserter) -> Eip7702Builder {
+        let provider = ProviderBuilder::new().on_mocked_client(asserter.clone()).erased();
+        Eip7702Builder::with_provider(Config::for_tests(), provider)
+    }
+
+    fn intent(wallet: &PrivateKeySigner, calls_signer: &PrivateKeySigner, validation_hash: B256) -> RelayIntent {
+        let authorization = Authorization {
+            chain_id: U256::from(CHAIN_ID),
+            address: Config::for_tests().wallet_core_address,
+            nonce: 0,
+        };
+        let signature = wallet.sign_hash_sync(&authorization.signature_hash()).unwrap();
+        let calls_signature = calls_signer.sign_hash_sync(&validation_hash).unwrap();
+        RelayIntent {
+            authorization: authorization.into_signed(signature),
+            calls: vec![IntentCall {
+                target: Address::repeat_byte(0x20),
+                value: U256::ZERO,
+                data: Bytes::new(),
+            }],
+            nonce: U256::ZERO,
+            signature: calls_signature.as_bytes().into(),
+        }
+    }
+
+    fn relay(status: RelayStatus) -> Relay {
+        Relay {
+            wallet: Address::ZERO,
+            nonce: U256::ZERO,
+            status,
+        }
+    }
+
+    fn failed() -> RelayStatus {
+        RelayStatus::Failed {
+            tx_hash: None,
+            error: "reverted".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_calls_signed_by_another_account() {
+        let wallet = PrivateKeySigner::random();
+        let validation_hash = B256::repeat_byte(0xaa);
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(CHAIN_ID));
+        asserter.push_success(&delegation_code(Config::for_tests().wallet_core_address));
+        asserter.push_success(&Bytes::from(Address::repeat_byte(0x55).abi_encode()));
+        asserter.push_success(&Bytes::from(U256::ZERO.abi_encode()));
+        asserter.push_success(&Bytes::from(validation_hash.abi_encode()));
+
+        let intent = intent(&wallet, &PrivateKeySigner::random(), validation_hash);
+        let result = builder(&asserter).verify_intent(&intent, Address::repeat_byte(0x99)).await;
+        assert!(matches!(result, Err(Error::InvalidValidationSignature { wallet: w, .. }) if w == wallet.address()));
+    }
+
+    #[tokio::test]
+    async fn rejects_signature_of_another_hash_for_undelegated_wallet() {
+        let wallet = PrivateKeySigner::random();
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(CHAIN_ID));
+        asserter.push_success(&Bytes::new());
+        asserter.push_success(&U64::ZERO);
+        asserter.push_success(&Bytes::new());
+        asserter.push_success(&Bytes::from(B256::repeat_byte(0xaa).abi_encode()));
+
+        // The wallet signed what the offline hash would be, not what WalletCore returns
+        let intent = intent(&wallet, &wallet, B256::repeat_byte(0xbb));
+        let result = builder(&asserter).verify_intent(&intent, Address::repeat_byte(0x99)).await;
+        assert!(matches!(result, Err(Error::InvalidValidationSignature { .. })));
+    }
+
+    #[tokio::test]
+    async fn rejects_stale_wallet_nonce() {
+        let wallet = PrivateKeySigner::random();
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(CHAIN_ID));
+        asserter.push_success(&delegation_code(Config::for_tests().wallet_core_address));
+        asserter.push_success(&Bytes::from(Address::repeat_byte(0x55).abi_encode()));
+        asserter.push_success(&Bytes::from(U256::from(4).abi_encode()));
+
+        let intent = intent(&wallet, &wallet, B256::repeat_byte(0xaa));
+        let result = builder(&asserter).verify_intent(&intent, Address::repeat_byte(0x99)).await;
+        assert!(matches!(result, Err(Error::WrongWalletNonce { expected, .. }) if expected == U256::from(4)));
+    }
+
+    #[test]
+    fn forgets_oldest_finished_relays() {
+        let mut relays = Relays::default();
+        relays.insert(B256::ZERO, relay(RelayStatus::Pending));
+        for i in 1..=MAX_FINISHED_RELAYS as u64 + 1 {
+            let id = B256::from(U256::from(i));
+            relays.insert(id, relay(RelayStatus::Pending));
+            relays.set_status(id, failed());
+        }
+
+        assert_eq!(relays.finished.len(), MAX_FINISHED_RELAYS);
+        assert!(!relays.by_id.contains_key(&B256::from(U256::from(1))));
+        assert!(relays.by_id.contains_key(&B256::from(U256::from(2))));
+        // Intents still in flight are never forgotten
+        assert!(relays.by_id.contains_key(&B256::ZERO));
+    }
+
+    #[test]
+    fn resubmitted_relay_is_no_longer_finished() {
+        let mut relays = Relays::default();
+        let id = B256::repeat_byte(1);
+        relays.insert(id, relay(RelayStatus::Pending));
+        relays.set_status(id, failed());
+        relays.insert(id, relay(RelayStatus::Pending));
+        assert!(relays.finished.is_empty());
+
+        relays.set_status(id, RelayStatus::Submitted { tx_hash: TxHash::ZERO });
+        relays.set_status(id, failed());
+        assert_eq!(relays.finished, [id]);
+    }
+
+    #[test]
+    fn maps_errors_to_rpc_codes() {
+        let reverted = Error::Reverted {
+            tx_hash: TxHash::ZERO,
+            block_number: None,
+        };
+        assert_eq!(rpc_error(reverted).code, EXECUTION_REVERTED);
+
+        let nonce = Error::WrongWalletNonce {
+            wallet: Address::ZERO,
+            expected: U256::from(1),
+            actual: U256::ZERO,
+        };
+        assert_eq!(rpc_error(nonce).code, RpcError::INVALID_PARAMS);
+    }
+
+    #[test]
+    fn status_serializes_with_tag() {
+        let status = RelayStatus::Mined {
+            tx_hash: TxHash::ZERO,
+            block_number: Some(7),
+            gas_used: 21000,
+        };
+        let value = serde_json::to_value(&status).unwrap();
+        assert_eq!(value["status"], "mined");
+        assert_eq!(value["blockNumber"], 7);
+        assert_eq!(serde_json::from_value::<RelayStatus>(value).unwrap(), status);
+    }
+}
@@ -0,0 +1,393 @@
+use std::{fmt, net::SocketAddr, sync::Arc};
+
+use alloy::{
+    consensus::SignableTransaction,
+    dyn_abi::TypedData,
+    eips::eip7702::Authorization,
+    network::TxSigner,
+    primitives::{Address, Bytes, ChainId, PrimitiveSignature as Signature, B256},
+    rpc::{
+        client::RpcClient,
+        types::{TransactionInput, TransactionRequest},
+    },
+    signers::{local::PrivateKeySigner, Signer, SignerSync},
+    transports::http::reqwest::Url,
+};
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::core::{
+    error::Result,
+    jsonrpc::{self, RpcError, RpcHandler},
+    signer::PayloadSigner,
+};
+
+/// Method returning the addresses held by a remote signer
+pub const ACCOUNTS_METHOD: &str = "signer_accounts";
+
+/// Method signing an EIP-7702 authorization tuple
+pub const SIGN_AUTHORIZATION_METHOD: &str = "signer_signAuthorization";
+
+/// Method signing EIP-712 typed data
+pub const SIGN_TYPED_DATA_METHOD: &str = "signer_signTypedData";
+
+/// Method signing a transaction
+pub const SIGN_TRANSACTION_METHOD: &str = "signer_signTransaction";
+
+/// Signer that forwards signing requests to a remote signer service
+///
+/// The service speaks JSON-RPC 2.0 over HTTP:
+///
+/// - `signer_accounts` with no params returns the addresses the service can sign for
+/// - `signer_signAuthorization` with params `[address, {chainId, address, nonce}]`
+/// - `signer_signTypedData` with params `[address, typedData]`, the `eth_signTypedData_v4` payload
+/// - `signer_signTransaction` with params `[address, transaction]`, an unsigned transaction request
+///
+/// Each signing method returns a 65-byte `r || s || v` signature over the hash the service
+/// computes from the payload. Raw hashes are never sent, so [`Signer::sign_hash`] fails.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    client: RpcClient,
+    url: Url,
+    address: Address,
+    chain_id: Option<ChainId>,
+}
+
+impl fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("url", &self.url)
+            .field("address", &self.address)
+            .field("chain_id", &self.chain_id)
+            .finish()
+    }
+}
+
+impl RemoteSigner {
+    /// Create a signer for `address`, whose key is held by the service at `url`
+    pub fn new(url: Url, address: Address) -> Self {
+        RemoteSigner {
+            client: RpcClient::new_http(url.clone()),
+            url,
+            address,
+            chain_id: None,
+        }
+    }
+
+    /// Get the URL of the remote signer service
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Get the address this signer signs for
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// List the addresses held by the service at `url`
+    pub async fn accounts(url: Url) -> Result<Vec<Address>> {
+        let client = RpcClient::new_http(url);
+        Ok(client.request_noparams(ACCOUNTS_METHOD).await?)
+    }
+
+    /// Ask the service to sign `payload`, whose signing hash is `hash`
+    async fn request_signature(
+        &self,
+        method: &'static str,
+        payload: impl serde::Serialize,
+        hash: B256,
+    ) -> alloy::signers::Result<Signature> {
+        let payload = serde_json::to_value(payload).map_err(alloy::signers::Error::other)?;
+        let signature: Bytes = self
+            .client
+            .request(method, (self.address, payload))
+            .await
+            .map_err(alloy::signers::Error::other)?;
+        let signature = Signature::try_from(signature.as_ref())?;
+
+        // Never trust the service to have used the right key or signed the payload it was sent
+        let signer = signature.recover_address_from_prehash(&hash)?;
+        if signer != self.address {
+            return Err(alloy::signers::Error::other(format!(
+                "remote signer returned a signature by {} instead of {}",
+                signer, self.address
+            )));
+        }
+
+        Ok(signature)
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    async fn sign_hash(&self, _hash: &B256) -> alloy::signers::Result<Signature> {
+        Err(alloy::signers::Error::other(
+            "remote signers sign authorizations, typed data and transactions, not raw hashes",
+        ))
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+#[async_trait]
+impl TxSigner<Signature> for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(&self, tx: &mut dyn SignableTransaction<Signature>) -> alloy::signers::Result<Signature> {
+        if let Some(chain_id) = self.chain_id
+            && !tx.set_chain_id_checked(chain_id)
+        {
+            return Err(alloy::signers::Error::TransactionChainIdMismatch {
+                signer: chain_id,
+                tx: tx.chain_id().unwrap_or_default(),
+            });
+        }
+        self.request_signature(SIGN_TRANSACTION_METHOD, unsigned_request(tx), tx.signature_hash())
+            .await
+    }
+}
+
+#[async_trait]
+impl PayloadSigner for RemoteSigner {
+    async fn sign_authorization(&self, authorization: &Authorization) -> alloy::signers::Result<Signature> {
+        self.request_signature(SIGN_AUTHORIZATION_METHOD, authorization, authorization.signature_hash())
+            .await
+    }
+
+    async fn sign_eip712(&self, typed_data: &TypedData) -> alloy::signers::Result<Signature> {
+        let hash = typed_data.eip712_signing_hash().map_err(alloy::signers::Error::other)?;
+        self.request_signature(SIGN_TYPED_DATA_METHOD, typed_data, hash)
+            .await
+    }
+}
+
+/// Describe a transaction as the unsigned request sent to the service
+fn unsigned_request(tx: &dyn SignableTransaction<Signature>) -> TransactionRequest {
+    let (gas_price, max_fee_per_gas) = if tx.is_dynamic_fee() {
+        (None, Some(tx.max_fee_per_gas()))
+    } else {
+        (Some(tx.max_fee_per_gas()), None)
+    };
+
+    TransactionRequest {
+        to: Some(tx.kind()),
+        gas_price,
+        max_fee_per_gas,
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas(),
+        gas: Some(tx.gas_limit()),
+        value: Some(tx.value()),
+        input: TransactionInput::new(tx.input().clone()),
+        nonce: Some(tx.nonce()),
+        chain_id: tx.chain_id(),
+        access_list: tx.access_list().cloned(),
+        authorization_list: tx.authorization_list().map(<[_]>::to_vec),
+        transaction_type: Some(tx.ty()),
+        ..Default::default()
+    }
+}
+
+/// Local stand-in for a remote signer service, signing with in-memory keys
+///
+/// The service stops when the mock is dropped.
+pub struct MockRemoteSigner {
+    url: Url,
+    task: JoinHandle<()>,
+}
+
+impl MockRemoteSigner {
+    /// Serve the remote signer protocol on `address` for the given keys
+    ///
+    /// Pass port 0 to pick a free port; the chosen URL is returned by [`Self::url`].
+    pub async fn spawn(address: SocketAddr, signers: Vec<PrivateKeySigner>) -> Result<Self> {
+        let listener = TcpListener::bind(address).await?;
+        let url = Url::parse(&format!("http://{}", listener.local_addr()?)).expect("valid URL");
+
+        let handler = Arc::new(Keyring { signers });
+        let task = tokio::spawn(async move {
+            if let Err(e) = jsonrpc::serve(listener, handler).await {
+                eprintln!("mock remote signer stopped: {}", e);
+            }
+        });
+
+        Ok(MockRemoteSigner { url, task })
+    }
+
+    /// Get the URL the mock listens on
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Create a [`RemoteSigner`] for one of the mock's keys
+    pub fn signer(&self, address: Address) -> RemoteSigner {
+        RemoteSigner::new(self.url.clone(), address)
+    }
+}
+
+impl Drop for MockRemoteSigner {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Keys served by [`MockRemoteSigner`]
+struct Keyring {
+    signers: Vec<PrivateKeySigner>,
+}
+
+impl Keyring {
+    /// Sign `hash` with the key of `address`
+    fn sign(&self, address: Address, hash: B256) -> Result<Value, RpcError> {
+        let signer = self
+            .signers
+            .iter()
+            .find(|signer| signer.address() == address)
+            .ok_or_else(|| RpcError::invalid_params(format!("unknown account {}", address)))?;
+        let signature = signer
+            .sign_hash_sync(&hash)
+            .map_err(|e| RpcError::new(RpcError::INTERNAL_ERROR, e.to_string()))?;
+        Ok(serde_json::to_value(Bytes::from(signature.as_bytes())).expect("bytes serialize"))
+    }
+}
+
+impl RpcHandler for Keyring {
+    async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            ACCOUNTS_METHOD => {
+                let accounts: Vec<Address> = self.signers.iter().map(PrivateKeySigner::address).collect();
+                Ok(serde_json::to_value(accounts).expect("addresses serialize"))
+            }
+            SIGN_AUTHORIZATION_METHOD => {
+                let (address, authorization): (Address, Authorization) = jsonrpc::params(params)?;
+                self.sign(address, authorization.signature_hash())
+            }
+            SIGN_TYPED_DATA_METHOD => {
+                let (address, typed_data): (Address, TypedData) = jsonrpc::params(params)?;
+                let hash = typed_data.eip712_signing_hash().map_err(RpcError::invalid_params)?;
+                self.sign(address, hash)
+            }
+            SIGN_TRANSACTION_METHOD => {
+                let (address, request): (Address, TransactionRequest) = jsonrpc::params(params)?;
+                let tx = request
+                    .build_typed_tx()
+                    .map_err(|_| RpcError::invalid_params("incomplete transaction"))?;
+                self.sign(address, tx.signature_hash())
+            }
+            _ => Err(RpcError::method_not_found(method)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        consensus::TxEip1559,
+        primitives::{address, TxKind, U256},
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::core::{contracts::Call, typed_data};
+
+    /// Service that signs every request with its own key, whatever account is asked for
+    struct Impostor(Keyring);
+
+    impl RpcHandler for Impostor {
+        async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+            let (_, payload): (Address, Value) = jsonrpc::params(params)?;
+            self.0.handle(method, json!([self.0.signers[0].address(), payload])).await
+        }
+    }
+
+    fn authorization() -> Authorization {
+        Authorization {
+            chain_id: U256::from(31337),
+            address: address!("0x7777777777777777777777777777777777777777"),
+            nonce: 4,
+        }
+    }
+
+    #[tokio::test]
+    async fn signs_typed_payloads_through_the_mock() {
+        let key = PrivateKeySigner::random();
+        let mock = MockRemoteSigner::spawn("127.0.0.1:0".parse().unwrap(), vec![key.clone()])
+            .await
+            .unwrap();
+        let signer = mock.signer(key.address());
+
+        assert_eq!(RemoteSigner::accounts(mock.url().clone()).await.unwrap(), vec![key.address()]);
+
+        let authorization = authorization();
+        let signature = signer.sign_authorization(&authorization).await.unwrap();
+        assert_eq!(
+            signature.recover_address_from_prehash(&authorization.signature_hash()).unwrap(),
+            key.address()
+        );
+
+        let domain = typed_data::wallet_core_domain(31337, key.address());
+        let calls = vec![Call {
+            target: address!("0x2222222222222222222222222222222222222222"),
+            value: U256::from(1),
+            data: Bytes::new(),
+        }];
+        let signature = signer
+            .sign_eip712(&typed_data::validation_typed_data(&domain, U256::ZERO, &calls))
+            .await
+            .unwrap();
+        let hash = typed_data::validation_hash(&domain, U256::ZERO, &calls);
+        assert_eq!(signature.recover_address_from_prehash(&hash).unwrap(), key.address());
+
+        let mut tx = TxEip1559 {
+            chain_id: 31337,
+            nonce: 1,
+            gas_limit: 21_000,
+            max_fee_per_gas: 2_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: TxKind::Call(address!("0x3333333333333333333333333333333333333333")),
+            value: U256::from(5),
+            ..Default::default()
+        };
+        let signature = signer.sign_transaction(&mut tx).await.unwrap();
+        assert_eq!(signature.recover_address_from_prehash(&tx.signature_hash()).unwrap(), key.address());
+
+        assert!(signer.sign_hash(&B256::ZERO).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_accounts() {
+        let mock = MockRemoteSigner::spawn("127.0.0.1:0".parse().unwrap(), vec![PrivateKeySigner::random()])
+            .await
+            .unwrap();
+        let signer = mock.signer(PrivateKeySigner::random().address());
+
+        assert!(signer.sign_authorization(&authorization()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_signatures_by_another_key() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let impostor = Arc::new(Impostor(Keyring {
+            signers: vec![PrivateKeySigner::random()],
+        }));
+        let task = tokio::spawn(jsonrpc::serve(listener, impostor));
+
+        let signer = RemoteSigner::new(url, PrivateKeySigner::random().address());
+        let error = signer.sign_authorization(&authorization()).await.unwrap_err();
+        task.abort();
+
+        assert!(error.to_string().contains("instead of"));
+    }
+}
@@ -0,0 +1,248 @@
+use std::time::Duration;
+
+use alloy::{
+    network::{TransactionBuilder, TxSigner},
+    primitives::{Address, PrimitiveSignature as Signature, TxHash, U256},
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+};
+use tokio::time::Instant;
+
+use crate::core::{
+    audit::{AttemptKind, AuditEvent},
+    builder::Eip7702Builder,
+    error::{Error, Result},
+    fees::{self, FeeEstimate},
+    nonce::is_nonce_error,
+};
+
+/// Gas of a plain ETH transfer, used by cancellations
+const CANCEL_GAS: u64 = 21_000;
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Fill the sender, nonce, gas limit and fees of a transaction, so that it can be
+    /// replaced with the same fields later
+    pub async fn prepare_transaction(&self, mut tx: TransactionRequest, from: Address) -> Result<TransactionRequest> {
+        tx.from = Some(from);
+        if tx.nonce.is_none() {
+            tx.nonce = Some(self.provider().get_transaction_count(from).pending().await?);
+        }
+        self.fill_gas_and_fees(&mut tx).await?;
+        Ok(tx)
+    }
+
+    /// Replace a pending transaction of `signer` with the same transaction at higher fees,
+    /// and wait until one of them is mined
+    pub async fn speed_up_transaction<S>(&self, tx_hash: TxHash, signer: &S) -> Result<TransactionReceipt>
+    where
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let mut tx = self.pending_transaction(tx_hash, signer.address()).await?;
+        self.bump_fees(&mut tx).await?;
+
+        let replacement = self
+            .submit_attempt(&tx, signer, AttemptKind::FeeBump, Some(tx_hash))
+            .await?;
+        self.confirm_transaction(tx, signer, vec![tx_hash, replacement], AttemptKind::FeeBump)
+            .await
+    }
+
+    /// Replace a pending transaction of `signer` with a zero-value self-send at higher fees,
+    /// and wait until one of them is mined
+    ///
+    /// The stuck transaction may still win; compare the receipt's hash to find out.
+    pub async fn cancel_transaction<S>(&self, tx_hash: TxHash, signer: &S) -> Result<TransactionReceipt>
+    where
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let from = signer.address();
+        let stuck = self.pending_transaction(tx_hash, from).await?;
+
+        let mut tx = TransactionRequest::default()
+            .with_from(from)
+            .with_to(from)
+            .with_value(U256::ZERO)
+            .with_gas_limit(CANCEL_GAS);
+        tx.nonce = stuck.nonce;
+        tx.gas_price = stuck.gas_price;
+        tx.max_fee_per_gas = stuck.max_fee_per_gas;
+        tx.max_priority_fee_per_gas = stuck.max_priority_fee_per_gas;
+        self.bump_fees(&mut tx).await?;
+
+        let cancellation = self
+            .submit_attempt(&tx, signer, AttemptKind::Cancel, Some(tx_hash))
+            .await?;
+        self.confirm_transaction(tx, signer, vec![tx_hash, cancellation], AttemptKind::Cancel)
+            .await
+    }
+
+    /// Broadcast one attempt of a prepared transaction and record it in the audit log, whose
+    /// hook reports it
+    pub(crate) async fn submit_attempt<S>(
+        &self,
+        tx: &TransactionRequest,
+        signer: &S,
+        kind: AttemptKind,
+        replaces: Option<TxHash>,
+    ) -> Result<TxHash>
+    where
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let from = signer.address();
+        let nonce = tx.nonce.unwrap_or_default();
+
+        match self.submit_transaction(tx.clone(), signer).await {
+            Ok(pending_tx) => {
+                let tx_hash = *pending_tx.tx_hash();
+                self.audit_log().record(AuditEvent::Submitted {
+                    kind,
+                    from,
+                    nonce,
+                    tx_hash,
+                    max_fee_per_gas: tx.max_fee_per_gas.or(tx.gas_price),
+                    max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                    replaces,
+                });
+                Ok(tx_hash)
+            }
+            Err(e) => {
+                self.audit_log().record(AuditEvent::Rejected {
+                    kind,
+                    from,
+                    nonce,
+                    max_fee_per_gas: tx.max_fee_per_gas.or(tx.gas_price),
+                    max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                    error: e.to_string(),
+                });
+                Err(e)
+            }
+        }
+    }
+
+    /// Wait until one of `tx_hashes`, which all share `tx`'s nonce, is mined
+    ///
+    /// Each time the confirmation timeout expires, `tx` is sent again as a `kind` attempt with
+    /// higher fees, until the policy's number of replacements is used up.
+    pub(crate) async fn confirm_transaction<S>(
+        &self,
+        mut tx: TransactionRequest,
+        signer: &S,
+        mut tx_hashes: Vec<TxHash>,
+        kind: AttemptKind,
+    ) -> Result<TransactionReceipt>
+    where
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let policy = self.config().replacement_policy;
+        let from = signer.address();
+        let nonce = tx.nonce.unwrap_or_default();
+
+        let mut bumps = 0;
+        loop {
+            if let Some(receipt) = self
+                .wait_for_any_receipt(&tx_hashes, policy.confirmation_timeout())
+                .await?
+            {
+                self.audit_log().record(AuditEvent::Mined {
+                    from,
+                    nonce,
+                    tx_hash: receipt.transaction_hash,
+                    block_number: receipt.block_number,
+                    success: receipt.status(),
+                });
+                return Ok(receipt);
+            }
+
+            self.audit_log().record(AuditEvent::TimedOut {
+                from,
+                nonce,
+                tx_hashes: tx_hashes.clone(),
+            });
+            if bumps == policy.max_fee_bumps() {
+                return Err(Error::ConfirmationTimeout { nonce, tx_hashes });
+            }
+            bumps += 1;
+
+            self.bump_fees(&mut tx).await?;
+            let replaces = tx_hashes.last().copied();
+            match self.submit_attempt(&tx, signer, kind, replaces).await {
+                Ok(tx_hash) => tx_hashes.push(tx_hash),
+                // An earlier attempt may have been mined meanwhile; the next round finds out
+                Err(e) if is_nonce_error(&e) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Get a pending transaction of `from` as a request that can be sent again
+    async fn pending_transaction(&self, tx_hash: TxHash, from: Address) -> Result<TransactionRequest> {
+        let cannot_replace = |reason: String| Error::CannotReplace { tx_hash, reason };
+
+        let tx = self
+            .provider()
+            .get_transaction_by_hash(tx_hash)
+            .await?
+            .ok_or_else(|| cannot_replace("the node does not know it".to_string()))?;
+        if let Some(block_number) = tx.block_number {
+            return Err(cannot_replace(format!("it was mined in block {}", block_number)));
+        }
+        let sender = tx.inner.signer();
+        if sender != from {
+            return Err(cannot_replace(format!("it was sent by {}, not {}", sender, from)));
+        }
+
+        // The authorization list, if any, is kept as is
+        Ok(tx.into_request().with_from(sender))
+    }
+
+    /// Raise the fees of a replacement by the policy's percentage, and at least to the
+    /// current estimate of the fee strategy
+    ///
+    /// Fails if the fee caps leave no room for the bump.
+    async fn bump_fees(&self, tx: &mut TransactionRequest) -> Result<()> {
+        let percent = self.config().replacement_policy.fee_bump_percent();
+        let estimate = self.estimate_fees().await?;
+        let needed = bump_fees(tx, percent, &estimate);
+        fees::cap_fees(tx, &self.config().fee_policy, Some(needed))
+    }
+
+    /// Poll for the receipt of any of `tx_hashes`, giving up after `timeout` if set
+    async fn wait_for_any_receipt(
+        &self,
+        tx_hashes: &[TxHash],
+        timeout: Option<Duration>,
+    ) -> Result<Option<TransactionReceipt>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let poll_interval = self.provider().client().poll_interval();
+
+        loop {
+            for tx_hash in tx_hashes {
+                if let Some(receipt) = self.provider().get_transaction_receipt(*tx_hash).await? {
+                    return Ok(Some(receipt));
+                }
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Ok(None);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+/// Raise the fees of `tx` by `percent`, by at least 1 wei, and at least to `estimate`,
+/// returning the smallest max fee the bump needs
+fn bump_fees(tx: &mut TransactionRequest, percent: u64, estimate: &FeeEstimate) -> u128 {
+    let bump = |fee: u128| (fee + fee * u128::from(percent) / 100).max(fee + 1);
+
+    if let Some(gas_price) = tx.gas_price {
+        let needed = bump(gas_price);
+        tx.gas_price = Some(needed.max(estimate.max_fee_per_gas));
+        return needed;
+    }
+
+    let priority = bump(tx.max_priority_fee_per_gas.unwrap_or_default()).max(estimate.max_priority_fee_per_gas);
+    let needed = bump(tx.max_fee_per_gas.unwrap_or_default());
+    tx.max_priority_fee_per_gas = Some(priority);
+    tx.max_fee_per_gas = Some(needed.max(estimate.max_fee_per_gas).max(priority));
+    needed
+}
@@ -0,0 +1,225 @@
+use std::fmt;
+
+use alloy::{
+    network::TransactionBuilder,
+    primitives::{Address, Bytes},
+    rpc::types::TransactionRequest,
+    sol_types::{ContractError, Panic, SolCall, SolInterface},
+};
+
+use crate::core::contracts::{
+    Call,
+    ERC20::ERC20Errors,
+    IWalletCore::{self, IWalletCoreErrors},
+};
+
+/// Decoded reason of a revert
+#[derive(Debug, PartialEq, Eq)]
+pub enum RevertReason {
+    /// A custom error of the WalletCore delegate
+    WalletCore(IWalletCoreErrors),
+    /// A custom error of an ERC20 token
+    Erc20(ERC20Errors),
+    /// A standard `Error(string)` revert
+    Message(String),
+    /// A standard `Panic(uint256)` revert
+    Panic(Panic),
+    /// A revert without any data
+    Empty,
+    /// Revert data that does not match any known error
+    Unknown(Bytes),
+}
+
+impl RevertReason {
+    /// Decode raw revert data
+    pub fn decode(data: &[u8]) -> RevertReason {
+        if data.is_empty() {
+            return RevertReason::Empty;
+        }
+
+        if let Ok(error) = ContractError::<IWalletCoreErrors>::abi_decode(data, true) {
+            return match error {
+                ContractError::CustomError(error) => RevertReason::WalletCore(error),
+                ContractError::Revert(revert) => RevertReason::Message(revert.reason),
+                ContractError::Panic(panic) => RevertReason::Panic(panic),
+            };
+        }
+
+        if let Ok(error) = ERC20Errors::abi_decode(data, true) {
+            return RevertReason::Erc20(error);
+        }
+
+        RevertReason::Unknown(Bytes::copy_from_slice(data))
+    }
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertReason::WalletCore(error) => write!(f, "WalletCore error {:?}", error),
+            RevertReason::Erc20(error) => write!(f, "ERC20 error {:?}", error),
+            RevertReason::Message(message) => write!(f, "reverted with reason \"{}\"", message),
+            RevertReason::Panic(panic) => write!(f, "{}", panic),
+            RevertReason::Empty => write!(f, "reverted without data"),
+            RevertReason::Unknown(data) => write!(f, "unknown revert data {}", data),
+        }
+    }
+}
+
+/// A revert of an `executeWithValidator` batch, attributed to the failing call when possible
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExecutionRevert {
+    /// Index of the failing call in the `Call[]` batch
+    pub call_index: Option<usize>,
+    /// Target of the failing call
+    pub target: Option<Address>,
+    /// Decoded reason
+    pub reason: RevertReason,
+}
+
+impl ExecutionRevert {
+    /// Decode revert data returned by an `executeWithValidator` execution over `calls`
+    pub fn decode(data: &[u8], calls: &[Call]) -> ExecutionRevert {
+        match RevertReason::decode(data) {
+            RevertReason::WalletCore(IWalletCoreErrors::CallFailed(failed)) => {
+                let call_index = usize::try_from(failed.index).ok();
+                let target = call_index
+                    .and_then(|index| calls.get(index))
+                    .map(|call| call.target);
+
+                ExecutionRevert {
+                    call_index,
+                    target,
+                    reason: RevertReason::decode(&failed.returnData),
+                }
+            }
+            reason => ExecutionRevert {
+                call_index: None,
+                target: None,
+                reason,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ExecutionRevert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.call_index, self.target) {
+            (Some(index), Some(target)) => write!(f, "call #{} to {} failed: {}", index, target, self.reason),
+            (Some(index), None) => write!(f, "call #{} failed: {}", index, self.reason),
+            _ => write!(f, "{}", self.reason),
+        }
+    }
+}
+
+/// Extract the `Call[]` batch of an `executeWithValidator` transaction, if it is one
+pub fn batch_calls(tx: &TransactionRequest) -> Vec<Call> {
+    tx.input()
+        .and_then(|input| IWalletCore::executeWithValidatorCall::abi_decode(input, true).ok())
+        .map(|call| call.calls)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{U256, address},
+        sol_types::{PanicKind, Revert, SolError},
+    };
+
+    use super::*;
+    use crate::core::contracts::ERC20;
+
+    const TOKEN: Address = address!("0x1111111111111111111111111111111111111111");
+    const RECEIVER: Address = address!("0x2222222222222222222222222222222222222222");
+
+    fn call_to(target: Address) -> Call {
+        Call {
+            target,
+            value: U256::ZERO,
+            data: Bytes::new(),
+        }
+    }
+
+    fn call_failed(index: u64, return_data: Vec<u8>) -> Vec<u8> {
+        IWalletCore::CallFailed {
+            index: U256::from(index),
+            returnData: return_data.into(),
+        }
+        .abi_encode()
+    }
+
+    #[test]
+    fn decodes_error_string() {
+        let data = Revert::from("not enough").abi_encode();
+        assert_eq!(RevertReason::decode(&data), RevertReason::Message("not enough".to_string()));
+    }
+
+    #[test]
+    fn decodes_panic() {
+        let data = Panic::from(PanicKind::UnderOverflow).abi_encode();
+        let RevertReason::Panic(panic) = RevertReason::decode(&data) else {
+            panic!("not a panic");
+        };
+        assert_eq!(panic.kind(), Some(PanicKind::UnderOverflow));
+    }
+
+    #[test]
+    fn decodes_empty_data() {
+        assert_eq!(RevertReason::decode(&[]), RevertReason::Empty);
+    }
+
+    #[test]
+    fn keeps_unknown_selector() {
+        let data = [0xde, 0xad, 0xbe, 0xef, 0x01];
+        assert_eq!(
+            RevertReason::decode(&data),
+            RevertReason::Unknown(Bytes::copy_from_slice(&data))
+        );
+    }
+
+    #[test]
+    fn attributes_call_failed_to_the_call() {
+        let erc20 = ERC20::ERC20InsufficientBalance {
+            sender: RECEIVER,
+            balance: U256::from(1),
+            needed: U256::from(2),
+        };
+        let data = call_failed(1, erc20.abi_encode());
+
+        let revert = ExecutionRevert::decode(&data, &[call_to(RECEIVER), call_to(TOKEN)]);
+        assert_eq!(revert.call_index, Some(1));
+        assert_eq!(revert.target, Some(TOKEN));
+        assert_eq!(revert.reason, RevertReason::Erc20(ERC20Errors::ERC20InsufficientBalance(erc20)));
+    }
+
+    #[test]
+    fn decodes_nested_reasons_of_call_failed() {
+        let calls = [call_to(TOKEN)];
+
+        let message = ExecutionRevert::decode(&call_failed(0, Revert::from("paused").abi_encode()), &calls);
+        assert_eq!(message.reason, RevertReason::Message("paused".to_string()));
+
+        let empty = ExecutionRevert::decode(&call_failed(0, Vec::new()), &calls);
+        assert_eq!(empty.reason, RevertReason::Empty);
+        assert_eq!(empty.to_string(), format!("call #0 to {} failed: reverted without data", TOKEN));
+    }
+
+    #[test]
+    fn call_failed_out_of_range_has_no_target() {
+        let revert = ExecutionRevert::decode(&call_failed(5, Vec::new()), &[call_to(TOKEN)]);
+        assert_eq!(revert.call_index, Some(5));
+        assert_eq!(revert.target, None);
+    }
+
+    #[test]
+    fn decodes_wallet_core_error_outside_a_call() {
+        let data = IWalletCore::InvalidSignature {}.abi_encode();
+        let revert = ExecutionRevert::decode(&data, &[]);
+        assert_eq!(revert.call_index, None);
+        assert!(matches!(
+            revert.reason,
+            RevertReason::WalletCore(IWalletCoreErrors::InvalidSignature(_))
+        ));
+    }
+}
@@ -0,0 +1,432 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy::{
+    network::{TransactionBuilder, TxSigner},
+    primitives::{Address, Bytes, PrimitiveSignature as Signature, U256},
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+    signers::local::PrivateKeySigner,
+    sol,
+    sol_types::{SolCall, SolValue},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    builder::Eip7702Builder,
+    contracts::{Call, ERC20},
+    error::{Error, Result},
+    signer::PayloadSigner,
+    types::Delegation,
+    validators::{ValidatorArgs, execute_with_validator_input},
+};
+
+sol! {
+    /// Immutable args of `contracts/SessionKeyValidator.sol`, cloned once per session key
+    #[derive(Debug, PartialEq, Eq)]
+    struct SessionKeyValidatorArgs {
+        address sessionKey;
+        address token;
+        uint256 maxAmount;
+        uint64 validUntil;
+        address[] recipients;
+    }
+
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract SessionKeyValidator {
+        error SessionCapExceeded(uint256 spent, uint256 maxAmount);
+
+        function spent(address wallet) external view returns (uint256);
+        function record(uint256 amount) external;
+    }
+}
+
+impl ValidatorArgs for SessionKeyValidatorArgs {
+    fn immutable_args(&self) -> Bytes {
+        self.abi_encode_params().into()
+    }
+}
+/// What a session key may do
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionPolicy {
+    /// Only token the session may transfer
+    pub token: Address,
+    /// Total amount the session may transfer, in base units
+    pub max_amount: U256,
+    /// Recipients the session may transfer to
+    pub recipients: Vec<Address>,
+    /// Unix timestamp after which the session is rejected
+    pub valid_until: u64,
+}
+
+impl SessionPolicy {
+    /// Check that `calls` only transfer the token to allowed recipients, within `remaining`,
+    /// before `now`; returns the amount they transfer
+    ///
+    /// This is a pre-flight check: the session's validator enforces the same policy on chain.
+    pub fn check(&self, calls: &[Call], remaining: U256, now: u64) -> Result<U256> {
+        let violation = |reason: String| Err(Error::SessionPolicyViolation(reason));
+
+        if now >= self.valid_until {
+            return violation(format!("session expired at {}", self.valid_until));
+        }
+
+        let mut total = U256::ZERO;
+        for (index, call) in calls.iter().enumerate() {
+            if call.target != self.token {
+                return violation(format!("call {} targets {}, not token {}", index, call.target, self.token));
+            }
+            if !call.value.is_zero() {
+                return violation(format!("call {} sends ETH", index));
+            }
+            let Ok(transfer) = ERC20::transferCall::abi_decode(&call.data, true) else {
+                return violation(format!("call {} is not an ERC20 transfer", index));
+            };
+            if !self.recipients.contains(&transfer.recipient) {
+                return violation(format!("call {} pays {}, which is not allowed", index, transfer.recipient));
+            }
+            total = total.saturating_add(transfer.amount);
+        }
+
+        if total > remaining {
+            return violation(format!("calls transfer {}, only {} left", total, remaining));
+        }
+        Ok(total)
+    }
+}
+
+/// Short-lived key executing a wallet's transfers within a [`SessionPolicy`]
+///
+/// The key signs batches for a `SessionKeyValidator` clone holding the policy, which the wallet
+/// owner adds with `addValidator`. The validator rejects other tokens, recipients, ETH and
+/// expired batches, and each batch ends with a `record` call on it that reverts once the cap is
+/// exceeded, so a leaked key cannot spend more. [`SessionKey::check`] runs the same checks
+/// before signing.
+#[derive(Debug, Clone)]
+pub struct SessionKey {
+    signer: PrivateKeySigner,
+    wallet: Address,
+    validator: Address,
+    policy: SessionPolicy,
+}
+
+impl SessionKey {
+    /// Restore a key created by [`Eip7702Builder::create_session`]
+    pub fn new(signer: PrivateKeySigner, wallet: Address, validator: Address, policy: SessionPolicy) -> Self {
+        SessionKey {
+            signer,
+            wallet,
+            validator,
+            policy,
+        }
+    }
+
+    /// Get the address of the key
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// Get the key that signs the session's batches
+    pub fn signer(&self) -> &PrivateKeySigner {
+        &self.signer
+    }
+
+    /// Get the wallet whose calls the key executes
+    pub fn wallet(&self) -> Address {
+        self.wallet
+    }
+
+    /// Get the validator clone enforcing the policy
+    pub fn validator(&self) -> Address {
+        self.validator
+    }
+
+    /// Get the policy of the key
+    pub fn policy(&self) -> &SessionPolicy {
+        &self.policy
+    }
+
+    /// Get the immutable args the validator is cloned with
+    pub fn validator_args(&self) -> SessionKeyValidatorArgs {
+        validator_args(self.address(), &self.policy)
+    }
+
+    /// Check `calls` against the policy now, given the amount already `spent`, returning the
+    /// amount they transfer
+    pub fn check(&self, calls: &[Call], spent: U256) -> Result<U256> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.policy.check(calls, self.policy.max_amount.saturating_sub(spent), now)
+    }
+
+    /// Append the `record` call counting `amount` on the validator, which the validator
+    /// requires as the last call of a batch
+    pub fn session_calls(&self, calls: &[Call], amount: U256) -> Vec<Call> {
+        let mut calls = calls.to_vec();
+        calls.push(Call {
+            target: self.validator,
+            value: U256::ZERO,
+            data: SessionKeyValidator::recordCall { amount }.abi_encode().into(),
+        });
+        calls
+    }
+}
+
+/// Immutable args restricting `session_key` to `policy`
+fn validator_args(session_key: Address, policy: &SessionPolicy) -> SessionKeyValidatorArgs {
+    SessionKeyValidatorArgs {
+        sessionKey: session_key,
+        token: policy.token,
+        maxAmount: policy.max_amount,
+        validUntil: policy.valid_until,
+        recipients: policy.recipients.clone(),
+    }
+}
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Generate a session key for `authority`'s delegated wallet and add a validator clone of
+    /// `implementation`, a deployed `SessionKeyValidator`, restricting it to `policy`
+    ///
+    /// The `addValidator` batch is signed by the owner and paid by `sender`.
+    pub async fn create_session<A, S>(
+        &self,
+        authority: &A,
+        implementation: Address,
+        policy: SessionPolicy,
+        sender: &S,
+    ) -> Result<(TransactionReceipt, SessionKey)>
+    where
+        A: PayloadSigner,
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let wallet = authority.address();
+        if !matches!(self.get_delegation(wallet).await?, Delegation::WalletCore(_)) {
+            return Err(Error::NotDelegated(wallet));
+        }
+
+        let signer = PrivateKeySigner::random();
+        let args = validator_args(signer.address(), &policy);
+        let (receipt, validator) = self.add_validator(authority, implementation, &args, sender).await?;
+        Ok((receipt, SessionKey::new(signer, wallet, validator, policy)))
+    }
+
+    /// Get the amount of the policy's token the wallet has transferred through the session's
+    /// validator, as counted by its `record` calls
+    pub async fn session_spent(&self, session: &SessionKey) -> Result<U256> {
+        let validator = SessionKeyValidator::new(session.validator(), self.provider());
+        Ok(validator.spent(session.wallet()).call().await?._0)
+    }
+
+    /// Check `calls` against the session policy and what the wallet has already spent, and
+    /// build the `executeWithValidator` transaction of the calls followed by their `record`
+    ///
+    /// The batch is signed by the session key; the transaction may be sent by anyone.
+    pub async fn build_session_transaction(&self, session: &SessionKey, calls: &[Call]) -> Result<TransactionRequest> {
+        let spent = self.session_spent(session).await?;
+        let amount = session.check(calls, spent)?;
+        let calls = session.session_calls(calls, amount);
+
+        let wallet = session.wallet();
+        let nonce = self.get_wallet_nonce(wallet).await?;
+        let validation_hash = self.get_validation_hash(wallet, nonce, &calls).await?;
+        let signature = self
+            .sign_validation_data(session.signer(), wallet, nonce, &calls, &validation_hash)
+            .await?;
+        let validation_data = Bytes::from((calls.clone(), nonce, signature).abi_encode_params());
+
+        Ok(TransactionRequest::default()
+            .with_to(wallet)
+            .with_input(execute_with_validator_input(&calls, session.validator(), validation_data)))
+    }
+
+    /// Execute `calls` with a session key in a transaction sent and paid by `sender`
+    pub async fn send_session_transaction<S>(
+        &self,
+        session: &SessionKey,
+        calls: &[Call],
+        sender: &S,
+    ) -> Result<TransactionReceipt>
+    where
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let tx = self
+            .build_session_transaction(session, calls)
+            .await?
+            .with_from(sender.address());
+        self.send_transaction(tx, sender).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{primitives::U64, providers::ProviderBuilder, transports::mock::Asserter};
+
+    use super::*;
+    use crate::core::{
+        calls::CallBuilder,
+        config::Config,
+        contracts::IWalletCore,
+        typed_data::{validation_hash, wallet_core_domain},
+    };
+
+    const WALLET: Address = Address::repeat_byte(0xaa);
+    const VALIDATOR: Address = Address::repeat_byte(0xbb);
+    const TOKEN: Address = Address::repeat_byte(0x20);
+    const ALLOWED: Address = Address::repeat_byte(0x33);
+    const NOW: u64 = 1_000;
+
+    fn policy() -> SessionPolicy {
+        SessionPolicy {
+            token: TOKEN,
+            max_amount: U256::from(100),
+            recipients: vec![ALLOWED],
+            valid_until: NOW + 60,
+        }
+    }
+
+    fn transfer(token: Address, to: Address, amount: u64) -> Call {
+        CallBuilder::new()
+            .erc20_transfer(token, to, U256::from(amount))
+            .build()
+            .remove(0)
+    }
+
+    fn violation(result: Result<U256>) -> String {
+        match result {
+            Err(Error::SessionPolicyViolation(reason)) => reason,
+            other => panic!("expected a policy violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn accepts_transfers_within_the_policy() {
+        let calls = [transfer(TOKEN, ALLOWED, 30), transfer(TOKEN, ALLOWED, 70)];
+        assert_eq!(policy().check(&calls, U256::from(100), NOW).unwrap(), U256::from(100));
+        assert_eq!(policy().check(&[], U256::ZERO, NOW).unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn rejects_expired_session() {
+        let calls = [transfer(TOKEN, ALLOWED, 1)];
+        assert!(violation(policy().check(&calls, U256::from(100), NOW + 60)).contains("expired"));
+    }
+
+    #[test]
+    fn rejects_other_tokens_and_recipients() {
+        let other = Address::repeat_byte(0x44);
+        let remaining = U256::from(100);
+
+        let reason = violation(policy().check(&[transfer(other, ALLOWED, 1)], remaining, NOW));
+        assert!(reason.contains("not token"));
+        let reason = violation(policy().check(&[transfer(TOKEN, other, 1)], remaining, NOW));
+        assert!(reason.contains("not allowed"));
+    }
+
+    #[test]
+    fn rejects_eth_and_other_calls() {
+        let remaining = U256::from(100);
+        let mut with_value = transfer(TOKEN, ALLOWED, 1);
+        with_value.value = U256::from(1);
+        assert!(violation(policy().check(&[with_value], remaining, NOW)).contains("sends ETH"));
+
+        let approve = Call {
+            target: TOKEN,
+            value: U256::ZERO,
+            data: ERC20::approveCall {
+                spender: ALLOWED,
+                amount: U256::from(1),
+            }
+            .abi_encode()
+            .into(),
+        };
+        assert!(violation(policy().check(&[approve], remaining, NOW)).contains("not an ERC20 transfer"));
+    }
+
+    #[test]
+    fn rejects_transfers_above_the_remaining_amount() {
+        let calls = [transfer(TOKEN, ALLOWED, 60), transfer(TOKEN, ALLOWED, 41)];
+        assert!(violation(policy().check(&calls, U256::from(100), NOW)).contains("only 100 left"));
+    }
+
+    #[test]
+    fn session_key_counts_what_was_spent() {
+        let policy = SessionPolicy {
+            valid_until: u64::MAX,
+            ..policy()
+        };
+        let key = SessionKey::new(PrivateKeySigner::random(), WALLET, VALIDATOR, policy);
+
+        let calls = [transfer(TOKEN, ALLOWED, 30)];
+        assert_eq!(key.check(&calls, U256::from(70)).unwrap(), U256::from(30));
+        assert!(key.check(&calls, U256::from(71)).is_err());
+    }
+
+    #[test]
+    fn validator_args_encode_as_params() {
+        let key = SessionKey::new(PrivateKeySigner::random(), WALLET, VALIDATOR, policy());
+        let args = key.validator_args().immutable_args();
+
+        // abi.decode(args, (address, address, uint256, uint64, address[])) in the validator
+        let decoded = <(Address, Address, U256, u64, Vec<Address>)>::abi_decode_params(&args, true).unwrap();
+        assert_eq!(decoded, (key.address(), TOKEN, U256::from(100), NOW + 60, vec![ALLOWED]));
+    }
+
+    fn builder(asserter: &Asserter) -> Eip7702Builder<impl Provider> {
+        Eip7702Builder::with_provider(Config::for_tests(), ProviderBuilder::new().on_mocked_client(asserter.clone()))
+    }
+
+    #[tokio::test]
+    async fn builds_batch_recording_its_amount() {
+        let policy = SessionPolicy {
+            valid_until: u64::MAX,
+            ..policy()
+        };
+        let key = SessionKey::new(PrivateKeySigner::random(), WALLET, VALIDATOR, policy);
+        let calls = [transfer(TOKEN, ALLOWED, 30)];
+        let nonce = U256::from(4);
+        let expected_calls = key.session_calls(&calls, U256::from(30));
+        let hash = validation_hash(&wallet_core_domain(31337, WALLET), nonce, &expected_calls);
+
+        let asserter = Asserter::new();
+        asserter.push_success(&Bytes::from(U256::from(70).abi_encode()));
+        asserter.push_success(&Bytes::from(Address::repeat_byte(0x55).abi_encode()));
+        asserter.push_success(&Bytes::from(nonce.abi_encode()));
+        asserter.push_success(&Bytes::from(hash.abi_encode()));
+        asserter.push_success(&U64::from(31337));
+        let tx = builder(&asserter).build_session_transaction(&key, &calls).await.unwrap();
+
+        assert_eq!(tx.to, Some(WALLET.into()));
+        let input = IWalletCore::executeWithValidatorCall::abi_decode(tx.input.input().unwrap(), true).unwrap();
+        assert_eq!(input.calls, expected_calls);
+        assert_eq!(input.validator, VALIDATOR);
+        let record = input.calls.last().unwrap();
+        assert_eq!(record.target, VALIDATOR);
+        assert_eq!(
+            SessionKeyValidator::recordCall::abi_decode(&record.data, true).unwrap().amount,
+            U256::from(30)
+        );
+
+        let (signed_calls, signed_nonce, signature) =
+            <(Vec<Call>, U256, Bytes)>::abi_decode_params(&input.validationData, true).unwrap();
+        assert_eq!((signed_calls, signed_nonce), (expected_calls, nonce));
+        let signature = Signature::try_from(signature.as_ref()).unwrap();
+        assert_eq!(signature.recover_address_from_prehash(&hash).unwrap(), key.address());
+    }
+
+    #[tokio::test]
+    async fn rejects_batch_above_what_is_left_before_signing() {
+        let policy = SessionPolicy {
+            valid_until: u64::MAX,
+            ..policy()
+        };
+        let key = SessionKey::new(PrivateKeySigner::random(), WALLET, VALIDATOR, policy);
+
+        let asserter = Asserter::new();
+        asserter.push_success(&Bytes::from(U256::from(90).abi_encode()));
+        let result = builder(&asserter)
+            .build_session_transaction(&key, &[transfer(TOKEN, ALLOWED, 30)])
+            .await;
+        assert!(matches!(result, Err(Error::SessionPolicyViolation(_))));
+    }
+}
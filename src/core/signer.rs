@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use alloy::{
+    consensus::SignableTransaction,
+    dyn_abi::TypedData,
+    eips::eip7702::Authorization,
+    network::TxSigner,
+    primitives::{Address, ChainId, PrimitiveSignature as Signature, B256},
+    signers::{
+        local::{coins_bip39::English, LocalSignerError, MnemonicBuilder, PrivateKeySigner},
+        Signer,
+    },
+};
+use async_trait::async_trait;
+
+use crate::core::remote_signer::RemoteSigner;
+
+/// Derivation path of the first account of a BIP-39 mnemonic
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// Signer of the payloads WalletCore flows sign: authorizations and EIP-712 typed data
+///
+/// Local keys hash the payload and sign the hash. A [`RemoteSigner`] sends the payload itself,
+/// so the service can check what it signs and computes the hash on its side.
+#[async_trait]
+pub trait PayloadSigner: Signer + Send + Sync {
+    /// Sign an EIP-7702 authorization tuple
+    async fn sign_authorization(&self, authorization: &Authorization) -> alloy::signers::Result<Signature> {
+        self.sign_hash(&authorization.signature_hash()).await
+    }
+
+    /// Sign EIP-712 typed data
+    async fn sign_eip712(&self, typed_data: &TypedData) -> alloy::signers::Result<Signature> {
+        let hash = typed_data.eip712_signing_hash().map_err(alloy::signers::Error::other)?;
+        self.sign_hash(&hash).await
+    }
+}
+
+impl PayloadSigner for PrivateKeySigner {}
+
+/// Signer loaded from configuration: a local key or a remote signer service
+#[derive(Debug, Clone)]
+pub enum WalletSigner {
+    /// Key held in memory, loaded from a private key, keystore or mnemonic
+    Local(PrivateKeySigner),
+    /// Key held by a remote signer service
+    Remote(RemoteSigner),
+}
+
+impl WalletSigner {
+    /// Get the address of the signer
+    pub fn address(&self) -> Address {
+        match self {
+            WalletSigner::Local(signer) => signer.address(),
+            WalletSigner::Remote(signer) => signer.address(),
+        }
+    }
+}
+
+impl From<PrivateKeySigner> for WalletSigner {
+    fn from(signer: PrivateKeySigner) -> Self {
+        WalletSigner::Local(signer)
+    }
+}
+
+impl From<RemoteSigner> for WalletSigner {
+    fn from(signer: RemoteSigner) -> Self {
+        WalletSigner::Remote(signer)
+    }
+}
+
+#[async_trait]
+impl Signer for WalletSigner {
+    async fn sign_hash(&self, hash: &B256) -> alloy::signers::Result<Signature> {
+        match self {
+            WalletSigner::Local(signer) => signer.sign_hash(hash).await,
+            WalletSigner::Remote(signer) => signer.sign_hash(hash).await,
+        }
+    }
+
+    fn address(&self) -> Address {
+        WalletSigner::address(self)
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        match self {
+            WalletSigner::Local(signer) => Signer::chain_id(signer),
+            WalletSigner::Remote(signer) => Signer::chain_id(signer),
+        }
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        match self {
+            WalletSigner::Local(signer) => signer.set_chain_id(chain_id),
+            WalletSigner::Remote(signer) => signer.set_chain_id(chain_id),
+        }
+    }
+}
+
+#[async_trait]
+impl TxSigner<Signature> for WalletSigner {
+    fn address(&self) -> Address {
+        WalletSigner::address(self)
+    }
+
+    async fn sign_transaction(&self, tx: &mut dyn SignableTransaction<Signature>) -> alloy::signers::Result<Signature> {
+        match self {
+            WalletSigner::Local(signer) => signer.sign_transaction(tx).await,
+            WalletSigner::Remote(signer) => signer.sign_transaction(tx).await,
+        }
+    }
+}
+
+#[async_trait]
+impl PayloadSigner for WalletSigner {
+    async fn sign_authorization(&self, authorization: &Authorization) -> alloy::signers::Result<Signature> {
+        match self {
+            WalletSigner::Local(signer) => signer.sign_authorization(authorization).await,
+            WalletSigner::Remote(signer) => signer.sign_authorization(authorization).await,
+        }
+    }
+
+    async fn sign_eip712(&self, typed_data: &TypedData) -> alloy::signers::Result<Signature> {
+        match self {
+            WalletSigner::Local(signer) => signer.sign_eip712(typed_data).await,
+            WalletSigner::Remote(signer) => signer.sign_eip712(typed_data).await,
+        }
+    }
+}
+
+/// Decrypt an encrypted JSON keystore
+pub fn from_keystore(path: &Path, password: impl AsRef<[u8]>) -> Result<PrivateKeySigner, LocalSignerError> {
+    PrivateKeySigner::decrypt_keystore(path, password)
+}
+
+/// Derive a key from a BIP-39 mnemonic, at [`DEFAULT_DERIVATION_PATH`] if no path is given
+pub fn from_mnemonic(phrase: &str, derivation_path: Option<&str>) -> Result<PrivateKeySigner, LocalSignerError> {
+    MnemonicBuilder::<English>::default()
+        .phrase(phrase)
+        .derivation_path(derivation_path.unwrap_or(DEFAULT_DERIVATION_PATH))?
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use alloy::primitives::{address, b256};
+
+    use super::*;
+
+    // PBKDF2 test vector of the Web3 Secret Storage definition, password "testpassword"
+    const KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    fn keystore_file() -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("tx7702-keystore-{}.json", std::process::id()));
+        fs::write(&path, KEYSTORE).unwrap();
+        path
+    }
+
+    #[test]
+    fn keystore_decrypts_to_its_key() {
+        let path = keystore_file();
+        let signer = from_keystore(&path, "testpassword").unwrap();
+        let wrong_password = from_keystore(&path, "wrong password");
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            signer.credential().to_bytes().as_slice(),
+            b256!("0x7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d").as_slice()
+        );
+        assert!(wrong_password.is_err());
+    }
+
+    #[test]
+    fn mnemonic_derives_the_default_path() {
+        let signer = from_mnemonic(TEST_MNEMONIC, None).unwrap();
+        assert_eq!(signer.address(), address!("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+    }
+
+    #[test]
+    fn mnemonic_derives_an_explicit_path() {
+        let signer = from_mnemonic(TEST_MNEMONIC, Some("m/44'/60'/0'/0/1")).unwrap();
+        assert_eq!(signer.address(), address!("0x70997970C51812dc3A010C7d01b50e0d17dc79C8"));
+        assert!(from_mnemonic(TEST_MNEMONIC, Some("not a path")).is_err());
+    }
+}
@@ -0,0 +1,371 @@
+use alloy::{
+    network::{TransactionBuilder, TransactionBuilder7702},
+    primitives::{Address, I256, U256},
+    providers::Provider,
+    rpc::types::{
+        Log, TransactionRequest,
+        simulate::{SimBlock, SimCallResult, SimulatePayload},
+        state::{StateOverride, StateOverridesBuilder},
+    },
+    sol_types::SolCall,
+};
+
+use crate::core::{
+    builder::Eip7702Builder,
+    contracts::{Call, ERC20},
+    error::{Error, Result},
+    fees::authorization_gas,
+    revert::{self, ExecutionRevert},
+    types::delegation_code,
+};
+
+/// How the delegation of the authorities was applied during a simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationMode {
+    /// The node applied the transaction's authorization list itself
+    AuthorizationList,
+    /// The delegation code was injected through a state override
+    StateOverride,
+}
+
+/// Token balance of an account before and after a simulated transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceDelta {
+    pub token: Address,
+    pub account: Address,
+    pub before: U256,
+    pub after: U256,
+}
+
+impl BalanceDelta {
+    /// Signed change of the balance
+    pub fn delta(&self) -> I256 {
+        I256::from_raw(self.after).wrapping_sub(I256::from_raw(self.before))
+    }
+}
+
+/// Outcome of a pre-flight simulation
+#[derive(Debug)]
+pub struct SimulationResult {
+    /// Whether the transaction executed without reverting
+    pub success: bool,
+    /// Gas used by the simulated execution, including the authorization list
+    pub gas_used: u64,
+    /// Result of `eth_estimateGas`, if the transaction does not revert
+    pub gas_estimate: Option<u64>,
+    /// Logs emitted by the transaction
+    pub logs: Vec<Log>,
+    /// Token balance changes of the involved addresses
+    pub balance_deltas: Vec<BalanceDelta>,
+    /// Decoded revert, if the transaction reverts
+    pub revert: Option<ExecutionRevert>,
+    /// How the authorization list was applied
+    pub mode: SimulationMode,
+}
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Simulate a transaction sent by `from` with `eth_simulateV1`, without broadcasting it.
+    ///
+    /// The transaction runs in a simulated block between two rounds of `balanceOf` calls, so the
+    /// balances after it are read from the simulated state. The authorization list is applied by
+    /// the node when it supports it; when the node rejects the list, the `0xef0100 || delegate`
+    /// code is injected into each authority with a state override.
+    pub async fn simulate(&self, tx: &TransactionRequest, from: Address) -> Result<SimulationResult> {
+        let calls = revert::batch_calls(tx);
+        let tracked = tracked_balances(tx, from, &calls);
+
+        let mut mode = SimulationMode::AuthorizationList;
+        let mut overrides = None;
+        let mut request = tx.clone().with_from(from);
+        let mut estimate = self
+            .provider()
+            .estimate_gas(request.clone())
+            .await
+            .map_err(Error::from);
+
+        if matches!(&estimate, Err(error) if authorization_list_unsupported(error)) {
+            mode = SimulationMode::StateOverride;
+            let state = delegation_overrides(tx)?;
+            request.authorization_list = None;
+            estimate = self
+                .provider()
+                .estimate_gas(request.clone())
+                .overrides(state.clone())
+                .await
+                .map_err(Error::from);
+            overrides = Some(state);
+        }
+
+        let gas_estimate = match estimate {
+            // The state override skips the intrinsic cost of the authorization list
+            Ok(gas) if mode == SimulationMode::StateOverride => Some(gas + authorization_gas(tx)),
+            Ok(gas) => Some(gas),
+            Err(error) if error.revert_data().is_some() => None,
+            Err(error) => return Err(error),
+        };
+
+        let results = match self.simulate_block(&tracked, request.clone(), overrides.clone()).await {
+            // Some nodes estimate with the authorization list but reject it in eth_simulateV1
+            Err(error) if mode == SimulationMode::AuthorizationList && authorization_list_unsupported(&error) => {
+                mode = SimulationMode::StateOverride;
+                request.authorization_list = None;
+                self.simulate_block(&tracked, request, Some(delegation_overrides(tx)?))
+                    .await?
+            }
+            results => results?,
+        };
+
+        let (executed, balance_deltas) = simulation_outcome(&tracked, results)?;
+        let revert = (!executed.status).then(|| ExecutionRevert::decode(&executed.return_data, &calls));
+        let gas_used = match mode {
+            SimulationMode::StateOverride => executed.gas_used + authorization_gas(tx),
+            SimulationMode::AuthorizationList => executed.gas_used,
+        };
+
+        Ok(SimulationResult {
+            success: executed.status,
+            gas_used,
+            gas_estimate,
+            logs: executed.logs,
+            balance_deltas,
+            revert,
+            mode,
+        })
+    }
+
+    /// Run `request` in one simulated block, between `balanceOf` calls for each tracked pair
+    async fn simulate_block(
+        &self,
+        tracked: &[(Address, Address)],
+        request: TransactionRequest,
+        overrides: Option<StateOverride>,
+    ) -> Result<Vec<SimCallResult>> {
+        let balance_calls: Vec<TransactionRequest> = tracked
+            .iter()
+            .map(|(token, account)| balance_of_request(*token, *account))
+            .collect();
+
+        let mut sim_calls = balance_calls.clone();
+        sim_calls.push(request);
+        sim_calls.extend(balance_calls);
+
+        let payload = SimulatePayload {
+            block_state_calls: vec![SimBlock {
+                block_overrides: None,
+                state_overrides: overrides,
+                calls: sim_calls,
+            }],
+            trace_transfers: false,
+            validation: false,
+            return_full_transactions: false,
+        };
+        let blocks = self.provider().simulate(&payload).await?;
+        Ok(blocks
+            .into_iter()
+            .next()
+            .map(|block| block.calls)
+            .unwrap_or_default())
+    }
+}
+
+/// Whether an RPC error means the node does not accept the authorization list of a transaction
+///
+/// Reverts, funding problems and transport failures are not; only these are worth retrying
+/// with the delegations injected by a state override.
+pub(crate) fn authorization_list_unsupported(error: &Error) -> bool {
+    const MARKERS: [&str; 6] = [
+        "authorizationlist",
+        "authorization list",
+        "7702",
+        "transaction type not supported",
+        "tx type not supported",
+        "unknown field",
+    ];
+
+    if error.revert_data().is_some() {
+        return false;
+    }
+    let Error::Rpc(rpc) = error else {
+        return false;
+    };
+    let Some(payload) = rpc.as_error_resp() else {
+        return false;
+    };
+    let message = payload.message.to_lowercase();
+    MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+/// Build a state override giving every authority of `tx` its delegation code
+pub(crate) fn delegation_overrides(tx: &TransactionRequest) -> Result<StateOverride> {
+    let mut overrides = StateOverridesBuilder::default();
+    for authorization in tx.authorization_list().into_iter().flatten() {
+        let authority = authorization.recover_authority()?;
+        overrides = overrides.with_code(authority, delegation_code(authorization.address));
+    }
+    Ok(overrides.into())
+}
+
+/// Collect the (token, account) pairs whose balances a transaction may change
+fn tracked_balances(tx: &TransactionRequest, from: Address, calls: &[Call]) -> Vec<(Address, Address)> {
+    let wallet = tx.to();
+    let mut tracked: Vec<(Address, Address)> = Vec::new();
+    let mut track = |token: Address, account: Address| {
+        if !tracked.contains(&(token, account)) {
+            tracked.push((token, account));
+        }
+    };
+
+    for call in calls {
+        let token = call.target;
+        if let Ok(transfer) = ERC20::transferCall::abi_decode(&call.data, true) {
+            if let Some(wallet) = wallet {
+                track(token, wallet);
+            }
+            track(token, transfer.recipient);
+        } else if let Ok(transfer) = ERC20::transferFromCall::abi_decode(&call.data, true) {
+            track(token, transfer.sender);
+            track(token, transfer.recipient);
+        } else {
+            continue;
+        }
+        track(token, from);
+    }
+
+    tracked
+}
+
+/// Build an `eth_call` request for `token.balanceOf(account)`
+fn balance_of_request(token: Address, account: Address) -> TransactionRequest {
+    TransactionRequest::default()
+        .with_to(token)
+        .with_input(ERC20::balanceOfCall { account }.abi_encode())
+}
+
+/// Split the results of a simulated block into the transaction and the balance changes
+///
+/// The block holds the `balanceOf` calls before the transaction, the transaction itself, then
+/// the same `balanceOf` calls after it.
+fn simulation_outcome(
+    tracked: &[(Address, Address)],
+    mut results: Vec<SimCallResult>,
+) -> Result<(SimCallResult, Vec<BalanceDelta>)> {
+    if results.len() != tracked.len() * 2 + 1 {
+        return Err(Error::Simulation(format!(
+            "expected {} call results, got {}",
+            tracked.len() * 2 + 1,
+            results.len()
+        )));
+    }
+
+    let after = results.split_off(tracked.len() + 1);
+    let executed = results.pop().expect("length checked above");
+    let balance_deltas = tracked
+        .iter()
+        .zip(results.iter().zip(&after))
+        .map(|((token, account), (before, after))| {
+            Ok(BalanceDelta {
+                token: *token,
+                account: *account,
+                before: decode_balance(before)?,
+                after: decode_balance(after)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((executed, balance_deltas))
+}
+
+/// Decode the result of a simulated `balanceOf` call
+fn decode_balance(result: &SimCallResult) -> Result<U256> {
+    ERC20::balanceOfCall::abi_decode_returns(&result.return_data, true)
+        .map(|balance| balance._0)
+        .map_err(|e| Error::Simulation(format!("cannot decode balanceOf result: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Bytes, address},
+        sol_types::SolValue,
+        transports::{RpcError, TransportErrorKind},
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    fn rpc_error(code: i64, message: &str, data: Option<&str>) -> Error {
+        let payload = json!({ "code": code, "message": message, "data": data });
+        Error::Rpc(RpcError::ErrorResp(serde_json::from_value(payload).unwrap()))
+    }
+
+    #[test]
+    fn rejected_authorization_list_is_unsupported() {
+        assert!(authorization_list_unsupported(&rpc_error(-32602, "transaction type not supported", None)));
+        assert!(authorization_list_unsupported(&rpc_error(
+            -32602,
+            "invalid params: unknown field `authorizationList`",
+            None
+        )));
+        assert!(authorization_list_unsupported(&rpc_error(-32000, "EIP-7702 is not enabled", None)));
+    }
+
+    #[test]
+    fn other_errors_are_not_unsupported() {
+        assert!(!authorization_list_unsupported(&rpc_error(3, "execution reverted", None)));
+        assert!(!authorization_list_unsupported(&rpc_error(-32000, "insufficient funds for gas * price + value", None)));
+        assert!(!authorization_list_unsupported(&rpc_error(
+            3,
+            "execution reverted: authorization list",
+            Some("0xdeadbeef")
+        )));
+        assert!(!authorization_list_unsupported(&Error::Rpc(TransportErrorKind::backend_gone())));
+        assert!(!authorization_list_unsupported(&Error::Simulation("no result".to_string())));
+    }
+
+    fn sim_result(return_data: Bytes, gas_used: u64, status: bool) -> SimCallResult {
+        SimCallResult {
+            return_data,
+            logs: Vec::new(),
+            gas_used,
+            status,
+            error: None,
+        }
+    }
+
+    fn balance(amount: u64) -> SimCallResult {
+        sim_result(Bytes::from(U256::from(amount).abi_encode()), 2_600, true)
+    }
+
+    #[test]
+    fn balances_after_come_from_the_simulated_state() {
+        let token = address!("0x2000000000000000000000000000000000000000");
+        let wallet = address!("0x1000000000000000000000000000000000000000");
+        let receiver = address!("0x3000000000000000000000000000000000000000");
+        let tracked = [(token, wallet), (token, receiver)];
+
+        let results = vec![
+            balance(100),
+            balance(0),
+            sim_result(Bytes::new(), 51_234, true),
+            balance(60),
+            balance(40),
+        ];
+        let (executed, deltas) = simulation_outcome(&tracked, results).unwrap();
+
+        assert!(executed.status);
+        assert_eq!(executed.gas_used, 51_234);
+        assert_eq!(deltas.len(), 2);
+        assert_eq!((deltas[0].account, deltas[0].before, deltas[0].after), (wallet, U256::from(100), U256::from(60)));
+        assert_eq!(deltas[0].delta(), I256::try_from(-40).unwrap());
+        assert_eq!((deltas[1].account, deltas[1].before, deltas[1].after), (receiver, U256::ZERO, U256::from(40)));
+    }
+
+    #[test]
+    fn missing_call_results_are_an_error() {
+        let token = address!("0x2000000000000000000000000000000000000000");
+        let tracked = [(token, Address::ZERO)];
+
+        let results = vec![balance(1), sim_result(Bytes::new(), 21_000, true)];
+        assert!(matches!(simulation_outcome(&tracked, results), Err(Error::Simulation(_))));
+    }
+}
@@ -0,0 +1,100 @@
+use alloy::{
+    dyn_abi::{Resolver, TypedData},
+    primitives::{Address, FixedBytes, U256},
+    sol,
+    sol_types::{Eip712Domain, SolStruct},
+};
+use serde_json::json;
+
+use crate::core::contracts::Call;
+
+/// EIP-712 domain name used by the WalletCore deployment
+pub const WALLET_CORE_DOMAIN_NAME: &str = "wallet-core";
+
+/// EIP-712 domain version used by the WalletCore deployment
+pub const WALLET_CORE_DOMAIN_VERSION: &str = "1.0.0";
+
+// Typed data signed by the wallet owner for `executeWithValidator`
+sol! {
+    #[allow(missing_docs)]
+    struct Execute {
+        uint256 nonce;
+        Call[] calls;
+    }
+}
+
+/// Build the EIP-712 domain of a delegated wallet.
+///
+/// Under EIP-7702 the code runs at the EOA, so the EOA itself is the verifying contract.
+pub fn wallet_core_domain(chain_id: u64, wallet_address: Address) -> Eip712Domain {
+    Eip712Domain::new(
+        Some(WALLET_CORE_DOMAIN_NAME.into()),
+        Some(WALLET_CORE_DOMAIN_VERSION.into()),
+        Some(U256::from(chain_id)),
+        Some(wallet_address),
+        None,
+    )
+}
+
+/// Compute the validation typed hash that `IWalletCore::getValidationTypedHash` returns
+pub fn validation_hash(domain: &Eip712Domain, nonce: U256, calls: &[Call]) -> FixedBytes<32> {
+    let execute = Execute {
+        nonce,
+        calls: calls.to_vec(),
+    };
+    execute.eip712_signing_hash(domain)
+}
+
+/// Build the typed data of the `Execute` struct, for signers that hash the payload themselves
+///
+/// It hashes to [`validation_hash`] for the same domain, nonce and calls.
+pub fn validation_typed_data(domain: &Eip712Domain, nonce: U256, calls: &[Call]) -> TypedData {
+    let mut resolver = Resolver::from_struct::<Execute>();
+    resolver
+        .ingest_string(domain.encode_type())
+        .expect("domain type is valid");
+
+    let calls: Vec<_> = calls
+        .iter()
+        .map(|call| json!({ "target": call.target, "value": call.value, "data": call.data }))
+        .collect();
+
+    TypedData {
+        domain: domain.clone(),
+        resolver,
+        primary_type: Execute::NAME.into(),
+        message: json!({ "nonce": nonce, "calls": calls }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{address, bytes, Bytes};
+
+    use super::*;
+
+    #[test]
+    fn typed_data_hashes_to_the_validation_hash() {
+        let domain = wallet_core_domain(31337, address!("0x1111111111111111111111111111111111111111"));
+        let calls = vec![
+            Call {
+                target: address!("0x2222222222222222222222222222222222222222"),
+                value: U256::from(5),
+                data: Bytes::new(),
+            },
+            Call {
+                target: address!("0x3333333333333333333333333333333333333333"),
+                value: U256::ZERO,
+                data: bytes!("a9059cbb"),
+            },
+        ];
+
+        let typed_data = validation_typed_data(&domain, U256::from(3), &calls);
+
+        assert_eq!(typed_data.primary_type, "Execute");
+        assert_eq!(
+            typed_data.eip712_signing_hash().unwrap(),
+            validation_hash(&domain, U256::from(3), &calls)
+        );
+    }
+}
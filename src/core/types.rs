@@ -1,4 +1,4 @@
-use alloy::primitives::{Address, address};
+use alloy::primitives::{Address, Bytes, address};
 
 /// Common addresses used throughout the application
 pub struct Addresses;
@@ -6,6 +6,53 @@ pub struct Addresses;
 impl Addresses {
     /// ECDSA validator address (address(1) for built-in ECDSA validation)
     pub const ECDSA_VALIDATOR: Address = address!("0x0000000000000000000000000000000000000001");
+
+    /// Multicall3, deployed at the same address on most EVM chains
+    pub const MULTICALL3: Address = address!("0xcA11bde05977b3631167028862bE2a173976CA11");
+}
+
+/// Prefix of the EIP-7702 delegation designator (`0xef0100 || address`)
+pub const DELEGATION_DESIGNATOR_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+
+/// Build the code an EOA carries once it delegates to `delegate`
+pub fn delegation_code(delegate: Address) -> Bytes {
+    if delegate.is_zero() {
+        // Delegating to the zero address clears the code
+        return Bytes::new();
+    }
+    [&DELEGATION_DESIGNATOR_PREFIX[..], delegate.as_slice()].concat().into()
+}
+
+/// Parse the delegate address out of an account's code, if it is a delegation designator
+pub fn parse_delegation_designator(code: &[u8]) -> Option<Address> {
+    match code.strip_prefix(&DELEGATION_DESIGNATOR_PREFIX[..]) {
+        Some(delegate) if delegate.len() == Address::len_bytes() => Some(Address::from_slice(delegate)),
+        _ => None,
+    }
+}
+
+/// Current EIP-7702 delegation status of an EOA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delegation {
+    /// The account has no code
+    NotDelegated,
+    /// The account delegates to the configured WalletCore
+    WalletCore(Address),
+    /// The account delegates to some other contract
+    Other(Address),
+}
+
+/// Chain an authorization is bound to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuthorizationChain {
+    /// Bind the authorization to the connected chain
+    #[default]
+    Current,
+    /// Sign with `chain_id = 0`, making the authorization valid on every chain.
+    ///
+    /// Anyone can replay it on any chain where the authority's nonce matches,
+    /// so the delegate must live at the same address everywhere.
+    Any,
 }
 
 /// Transaction pattern types
@@ -15,4 +62,43 @@ pub enum TransactionPattern {
     SelfAuthorization,
     /// Relayer pattern: User signs off-chain, relayer submits transaction
     RelayerPattern,
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELEGATE: Address = address!("0x80296FF8D1ED46f8e3C7992664D13B833504c2Bb");
+
+    #[test]
+    fn parses_designator() {
+        let code = [&DELEGATION_DESIGNATOR_PREFIX[..], DELEGATE.as_slice()].concat();
+        assert_eq!(code.len(), 23);
+        assert_eq!(parse_delegation_designator(&code), Some(DELEGATE));
+    }
+
+    #[test]
+    fn round_trips_delegation_code() {
+        assert_eq!(parse_delegation_designator(&delegation_code(DELEGATE)), Some(DELEGATE));
+    }
+
+    #[test]
+    fn rejects_wrong_prefix() {
+        let code = [&[0xef, 0x01, 0x01][..], DELEGATE.as_slice()].concat();
+        assert_eq!(parse_delegation_designator(&code), None);
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let code = [&DELEGATION_DESIGNATOR_PREFIX[..], DELEGATE.as_slice()].concat();
+        assert_eq!(parse_delegation_designator(&code[..22]), None);
+        assert_eq!(parse_delegation_designator(&[&code[..], &[0x00]].concat()), None);
+        assert_eq!(parse_delegation_designator(&DELEGATION_DESIGNATOR_PREFIX), None);
+    }
+
+    #[test]
+    fn rejects_empty_code() {
+        assert_eq!(parse_delegation_designator(&[]), None);
+        assert_eq!(parse_delegation_designator(&delegation_code(Address::ZERO)), None);
+    }
+}
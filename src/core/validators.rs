@@ -0,0 +1,359 @@
+use alloy::{
+    network::{TransactionBuilder, TxSigner},
+    primitives::{Address, Bytes, PrimitiveSignature as Signature, TxHash},
+    providers::Provider,
+    rpc::types::{Filter, TransactionReceipt, TransactionRequest},
+    sol,
+    sol_types::{SolCall, SolEvent, SolValue},
+    transports::{RpcError, TransportErrorKind},
+};
+use serde::Serialize;
+
+use crate::core::{
+    builder::Eip7702Builder,
+    contracts::{Call, IWalletCore},
+    error::{Error, Result},
+    signer::PayloadSigner,
+    types::Delegation,
+};
+
+/// Blocks covered by each `eth_getLogs` request of [`Eip7702Builder::list_validators`]; many
+/// providers reject wider ranges
+pub const LOG_CHUNK_BLOCKS: u64 = 10_000;
+
+/// `immutableArgs` a validator implementation is cloned with by `addValidator`
+pub trait ValidatorArgs {
+    /// ABI-encode the arguments as the clone reads them
+    fn immutable_args(&self) -> Bytes;
+}
+
+impl ValidatorArgs for Bytes {
+    fn immutable_args(&self) -> Bytes {
+        self.clone()
+    }
+}
+
+// Arguments of the validator implementations known to the builder
+sol! {
+    /// ECDSA validator accepting signatures from one fixed key instead of the wallet owner
+    #[derive(Debug, PartialEq, Eq)]
+    struct EcdsaValidatorArgs {
+        address signer;
+    }
+}
+
+impl ValidatorArgs for EcdsaValidatorArgs {
+    fn immutable_args(&self) -> Bytes {
+        self.abi_encode_params().into()
+    }
+}
+
+/// Validator added to a wallet, found from its `ValidatorAdded` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstalledValidator {
+    /// Clone to pass to `executeWithValidator`
+    pub address: Address,
+    pub block_number: Option<u64>,
+    pub tx_hash: Option<TxHash>,
+}
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Get the address `addValidator` clones `implementation` to for a delegated wallet
+    pub async fn compute_validator_address(
+        &self,
+        wallet: Address,
+        implementation: Address,
+        immutable_args: &impl ValidatorArgs,
+    ) -> Result<Address> {
+        let core = IWalletCore::new(wallet, self.provider());
+        Ok(core
+            .computeValidatorAddress(implementation, immutable_args.immutable_args())
+            .call()
+            .await?
+            ._0)
+    }
+
+    /// Create the call that adds a validator to `wallet`; WalletCore only accepts it from the
+    /// wallet itself, so it must run inside one of the wallet's batches
+    pub fn add_validator_call(
+        &self,
+        wallet: Address,
+        implementation: Address,
+        immutable_args: &impl ValidatorArgs,
+    ) -> Call {
+        Call {
+            target: wallet,
+            value: Default::default(),
+            data: IWalletCore::addValidatorCall {
+                validatorImpl: implementation,
+                immutableArgs: immutable_args.immutable_args(),
+            }
+            .abi_encode()
+            .into(),
+        }
+    }
+
+    /// Add a validator to `authority`'s wallet in a batch signed by its owner and paid by
+    /// `sender`, returning the address of the new validator
+    pub async fn add_validator<A, S>(
+        &self,
+        authority: &A,
+        implementation: Address,
+        immutable_args: &impl ValidatorArgs,
+        sender: &S,
+    ) -> Result<(TransactionReceipt, Address)>
+    where
+        A: PayloadSigner,
+        S: TxSigner<Signature> + Clone + Send + Sync + 'static,
+    {
+        let wallet = authority.address();
+        let calls = vec![self.add_validator_call(wallet, implementation, immutable_args)];
+        let tx = self
+            .build_signed_execute_transaction(authority, sender.address(), &calls)
+            .await?;
+        let receipt = self.send_transaction(tx, sender).await?;
+
+        let added = receipt
+            .inner
+            .logs()
+            .iter()
+            .filter(|log| log.address() == wallet)
+            .find_map(|log| log.log_decode::<IWalletCore::ValidatorAdded>().ok())
+            .map(|log| log.inner.data.validator);
+        let validator = match added {
+            Some(validator) => validator,
+            // Adding a validator twice deploys nothing
+            None => self.compute_validator_address(wallet, implementation, immutable_args).await?,
+        };
+
+        Ok((receipt, validator))
+    }
+
+    /// List the validators added to a wallet, oldest first
+    ///
+    /// WalletCore has no call to remove a validator, so every added validator is listed. Logs
+    /// are read in chunks of [`LOG_CHUNK_BLOCKS`] from `from_block`, or else from the block
+    /// WalletCore was deployed in, since no wallet can add a validator before it exists.
+    pub async fn list_validators(&self, wallet: Address, from_block: Option<u64>) -> Result<Vec<InstalledValidator>> {
+        let latest = self.provider().get_block_number().await?;
+        if !matches!(self.get_delegation(wallet).await?, Delegation::WalletCore(_)) {
+            return Err(Error::NotDelegated(wallet));
+        }
+        let from = match from_block {
+            Some(block) => block,
+            None => self.deployment_block(self.config().wallet_core_address, latest).await?,
+        };
+
+        let mut validators = Vec::new();
+        for (from_block, to_block) in log_ranges(from, latest, LOG_CHUNK_BLOCKS) {
+            let filter = Filter::new()
+                .address(wallet)
+                .event_signature(IWalletCore::ValidatorAdded::SIGNATURE_HASH)
+                .from_block(from_block)
+                .to_block(to_block);
+            let logs = self.provider().get_logs(&filter).await?;
+
+            validators.extend(logs.iter().filter_map(|log| {
+                let added = log.log_decode::<IWalletCore::ValidatorAdded>().ok()?;
+                Some(InstalledValidator {
+                    address: added.inner.data.validator,
+                    block_number: log.block_number,
+                    tx_hash: log.transaction_hash,
+                })
+            }));
+        }
+        Ok(validators)
+    }
+
+    /// Find the block `contract` was deployed in, up to `latest`
+    ///
+    /// Deployed code is never removed since EIP-6780, so the code of the contract is bisected
+    /// over past blocks. Nodes that have pruned the state of those blocks make the search start
+    /// from the genesis block; other errors are returned.
+    async fn deployment_block(&self, contract: Address, latest: u64) -> Result<u64> {
+        let (mut low, mut high) = (0, latest);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let code = match self.provider().get_code_at(contract).number(middle).await {
+                Ok(code) => code,
+                Err(e) if state_unavailable(&e) => return Ok(0),
+                Err(e) => return Err(e.into()),
+            };
+            if code.is_empty() {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Sign `calls` with `validator_signer` and build the `executeWithValidator` transaction that
+    /// runs them through `validator` on an already delegated wallet
+    pub async fn build_validator_execute_transaction<V: PayloadSigner>(
+        &self,
+        wallet: Address,
+        validator: Address,
+        validator_signer: &V,
+        calls: &[Call],
+    ) -> Result<TransactionRequest> {
+        if !matches!(self.get_delegation(wallet).await?, Delegation::WalletCore(_)) {
+            return Err(Error::NotDelegated(wallet));
+        }
+
+        let nonce = self.get_wallet_nonce(wallet).await?;
+        let validation_hash = self.get_validation_hash(wallet, nonce, calls).await?;
+        let validation_data = self
+            .sign_validation_data(validator_signer, wallet, nonce, calls, &validation_hash)
+            .await?;
+
+        Ok(TransactionRequest::default()
+            .with_to(wallet)
+            .with_input(execute_with_validator_input(calls, validator, validation_data)))
+    }
+}
+
+/// Encode an `executeWithValidator` call
+pub fn execute_with_validator_input(calls: &[Call], validator: Address, validation_data: Bytes) -> Bytes {
+    IWalletCore::executeWithValidatorCall {
+        calls: calls.to_vec(),
+        validator,
+        validationData: validation_data,
+    }
+    .abi_encode()
+    .into()
+}
+
+/// Whether an RPC error means the node no longer has the state of the requested block
+fn state_unavailable(error: &RpcError<TransportErrorKind>) -> bool {
+    const MARKERS: [&str; 5] = [
+        "missing trie node",
+        "state is not available",
+        "state not available",
+        "historical state",
+        "pruned",
+    ];
+
+    let Some(payload) = error.as_error_resp() else {
+        return false;
+    };
+    let message = payload.message.to_lowercase();
+    MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+/// Split `from..=to` into inclusive block ranges of at most `chunk` blocks
+pub(crate) fn log_ranges(from: u64, to: u64, chunk: u64) -> impl Iterator<Item = (u64, u64)> {
+    (from..=to)
+        .step_by(chunk as usize)
+        .map(move |start| (start, start.saturating_add(chunk - 1).min(to)))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{B256, U64},
+        providers::ProviderBuilder,
+        transports::mock::Asserter,
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::core::{config::Config, types::delegation_code};
+
+    fn builder(asserter: &Asserter) -> Eip7702Builder<impl Provider> {
+        Eip7702Builder::with_provider(Config::for_tests(), ProviderBuilder::new().on_mocked_client(asserter.clone()))
+    }
+
+    fn designator() -> Bytes {
+        delegation_code(Config::for_tests().wallet_core_address)
+    }
+
+    #[test]
+    fn splits_log_ranges_into_chunks() {
+        let ranges: Vec<_> = log_ranges(5, 25_004, LOG_CHUNK_BLOCKS).collect();
+        assert_eq!(ranges, [(5, 10_004), (10_005, 20_004), (20_005, 25_004)]);
+        assert_eq!(log_ranges(7, 7, LOG_CHUNK_BLOCKS).collect::<Vec<_>>(), [(7, 7)]);
+    }
+
+    fn added_log(wallet: Address, validator: Address) -> serde_json::Value {
+        json!([{
+            "address": wallet,
+            "topics": [IWalletCore::ValidatorAdded::SIGNATURE_HASH],
+            "data": Bytes::from(validator.abi_encode()),
+            "blockNumber": "0x6",
+            "blockHash": B256::repeat_byte(2),
+            "transactionHash": TxHash::repeat_byte(1),
+            "transactionIndex": "0x0",
+            "logIndex": "0x0",
+            "removed": false,
+        }])
+    }
+
+    #[tokio::test]
+    async fn lists_validators_from_the_wallet_core_deployment_block() {
+        let wallet = Address::repeat_byte(0xaa);
+        let validator = Address::repeat_byte(0xbb);
+        let wallet_core_code = Bytes::from_static(&[0x60, 0x80]);
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(8));
+        asserter.push_success(&designator());
+        // Bisection over blocks 0..=8: WalletCore missing at 4, deployed at 6 and 5
+        asserter.push_success(&Bytes::new());
+        asserter.push_success(&wallet_core_code);
+        asserter.push_success(&wallet_core_code);
+        asserter.push_success(&added_log(wallet, validator));
+
+        let validators = builder(&asserter).list_validators(wallet, None).await.unwrap();
+        assert_eq!(
+            validators,
+            [InstalledValidator {
+                address: validator,
+                block_number: Some(6),
+                tx_hash: Some(TxHash::repeat_byte(1)),
+            }]
+        );
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn lists_validators_from_an_explicit_block() {
+        let wallet = Address::repeat_byte(0xaa);
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(25_000));
+        asserter.push_success(&designator());
+        asserter.push_success(&added_log(wallet, Address::repeat_byte(0xbb)));
+        asserter.push_success(&json!([]));
+
+        let validators = builder(&asserter).list_validators(wallet, Some(12_000)).await.unwrap();
+        assert_eq!(validators.len(), 1);
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reads_logs_from_genesis_in_chunks_without_historical_state() {
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(15_000));
+        asserter.push_success(&designator());
+        asserter.push_failure_msg("missing trie node 0x12 (path ) state 0x34 is not available");
+        asserter.push_success(&json!([]));
+        asserter.push_success(&json!([]));
+
+        let validators = builder(&asserter)
+            .list_validators(Address::repeat_byte(0xaa), None)
+            .await
+            .unwrap();
+        assert!(validators.is_empty());
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn other_errors_do_not_fall_back_to_genesis() {
+        let asserter = Asserter::new();
+        asserter.push_success(&U64::from(15_000));
+        asserter.push_success(&designator());
+        asserter.push_failure_msg("rate limit exceeded");
+
+        let result = builder(&asserter).list_validators(Address::repeat_byte(0xaa), None).await;
+        assert!(matches!(result, Err(Error::Rpc(_))));
+    }
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+
+use alloy::{
+    eips::eip7702::{constants::SECP256K1N_HALF, SignedAuthorization},
+    primitives::{Address, Bytes, U256},
+    providers::Provider,
+};
+
+use crate::core::{
+    builder::Eip7702Builder,
+    error::{Error, Result},
+    types::parse_delegation_designator,
+};
+
+/// What a received authorization is expected to contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpectedAuthorization {
+    /// EOA that must have signed the authorization
+    pub authority: Address,
+    /// Contract the EOA must delegate to
+    pub delegate: Address,
+    /// Chain the authorization must be bound to
+    pub chain_id: u64,
+    /// Account that will submit the transaction carrying the authorization
+    pub sender: Address,
+    /// Accept `chain_id = 0`, reporting it as a warning instead of an issue
+    pub allow_any_chain: bool,
+}
+
+/// Reason an authorization is rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthorizationIssue {
+    /// `y_parity` is neither 0 nor 1
+    InvalidYParity(u8),
+    /// `s` is above secp256k1n/2
+    HighS(U256),
+    /// No authority can be recovered from the signature
+    Unrecoverable(String),
+    /// The authorization is bound to another chain
+    WrongChain { expected: u64, actual: U256 },
+    /// The authorization uses `chain_id = 0` and is valid on every chain
+    ChainAgnostic,
+    /// The nonce is `2^64 - 1`, which the protocol never accepts
+    NonceOverflow,
+    /// The nonce does not match the authority's nonce at execution time
+    WrongNonce { expected: u64, actual: u64 },
+    /// The authority holds contract code other than a delegation designator
+    AuthorityHasCode,
+    /// The signature was made by another account
+    WrongAuthority { expected: Address, actual: Address },
+    /// The authorization points at another delegate
+    WrongDelegate { expected: Address, actual: Address },
+}
+
+impl AuthorizationIssue {
+    /// Whether the protocol itself would skip the authorization for this reason
+    pub fn is_skipped_by_protocol(&self) -> bool {
+        !matches!(
+            self,
+            AuthorizationIssue::ChainAgnostic
+                | AuthorizationIssue::WrongAuthority { .. }
+                | AuthorizationIssue::WrongDelegate { .. }
+        )
+    }
+}
+
+impl fmt::Display for AuthorizationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthorizationIssue::InvalidYParity(y_parity) => write!(f, "invalid y parity {}", y_parity),
+            AuthorizationIssue::HighS(s) => write!(f, "signature s value {} is not in the lower half order", s),
+            AuthorizationIssue::Unrecoverable(reason) => write!(f, "cannot recover authority: {}", reason),
+            AuthorizationIssue::WrongChain { expected, actual } => {
+                write!(f, "bound to chain {}, expected {}", actual, expected)
+            }
+            AuthorizationIssue::ChainAgnostic => write!(f, "chain id 0 makes the authorization replayable on every chain"),
+            AuthorizationIssue::NonceOverflow => write!(f, "nonce 2^64 - 1 is never valid"),
+            AuthorizationIssue::WrongNonce { expected, actual } => write!(f, "nonce {}, expected {}", actual, expected),
+            AuthorizationIssue::AuthorityHasCode => write!(f, "authority is a contract"),
+            AuthorizationIssue::WrongAuthority { expected, actual } => {
+                write!(f, "signed by {}, expected {}", actual, expected)
+            }
+            AuthorizationIssue::WrongDelegate { expected, actual } => {
+                write!(f, "delegates to {}, expected {}", actual, expected)
+            }
+        }
+    }
+}
+
+/// Detailed result of checking a signed authorization
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorizationReport {
+    /// Authority recovered from the signature
+    pub authority: Option<Address>,
+    /// Every problem found, empty if the authorization is valid
+    pub issues: Vec<AuthorizationIssue>,
+    /// Accepted risks, such as a chain-agnostic authorization when any chain is allowed
+    pub warnings: Vec<AuthorizationIssue>,
+}
+
+impl AuthorizationReport {
+    /// Whether the authorization passed every check
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Whether the protocol would skip the authorization when processing the transaction
+    pub fn is_skipped_by_protocol(&self) -> bool {
+        self.issues.iter().any(AuthorizationIssue::is_skipped_by_protocol)
+    }
+}
+
+impl fmt::Display for AuthorizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.issues.is_empty() {
+            write!(f, "valid")?;
+        } else {
+            let issues: Vec<String> = self.issues.iter().map(ToString::to_string).collect();
+            write!(f, "{}", issues.join("; "))?;
+        }
+        for warning in &self.warnings {
+            write!(f, "; warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+/// Describe the replay risk of an authorization signed with `chain_id = 0`, if it is one
+pub fn chain_agnostic_warning(authorization: &SignedAuthorization) -> Option<String> {
+    if !authorization.chain_id.is_zero() {
+        return None;
+    }
+    let authority = authorization
+        .recover_authority()
+        .map_or_else(|_| "an unknown authority".to_string(), |authority| authority.to_string());
+    Some(format!(
+        "chain-agnostic authorization for {} can be replayed on every chain where its nonce is {}",
+        authority, authorization.nonce
+    ))
+}
+
+/// Check the parts of an authorization that do not depend on chain state
+pub fn inspect_authorization(
+    authorization: &SignedAuthorization,
+    expected: &ExpectedAuthorization,
+) -> AuthorizationReport {
+    let mut issues = Vec::new();
+    let mut warnings = Vec::new();
+
+    if authorization.y_parity() > 1 {
+        issues.push(AuthorizationIssue::InvalidYParity(authorization.y_parity()));
+    }
+    if authorization.s() > SECP256K1N_HALF {
+        issues.push(AuthorizationIssue::HighS(authorization.s()));
+    }
+
+    let chain_id = authorization.chain_id;
+    if chain_id.is_zero() && expected.allow_any_chain {
+        warnings.push(AuthorizationIssue::ChainAgnostic);
+    } else if chain_id.is_zero() {
+        issues.push(AuthorizationIssue::ChainAgnostic);
+    } else if chain_id != U256::from(expected.chain_id) {
+        issues.push(AuthorizationIssue::WrongChain {
+            expected: expected.chain_id,
+            actual: chain_id,
+        });
+    }
+
+    if authorization.nonce == u64::MAX {
+        issues.push(AuthorizationIssue::NonceOverflow);
+    }
+
+    if authorization.address != expected.delegate {
+        issues.push(AuthorizationIssue::WrongDelegate {
+            expected: expected.delegate,
+            actual: authorization.address,
+        });
+    }
+
+    let authority = match authorization.recover_authority() {
+        Ok(authority) => Some(authority),
+        // High s is already reported above
+        Err(_) if authorization.s() > SECP256K1N_HALF => None,
+        Err(e) => {
+            issues.push(AuthorizationIssue::Unrecoverable(e.to_string()));
+            None
+        }
+    };
+    if let Some(authority) = authority.filter(|authority| *authority != expected.authority) {
+        issues.push(AuthorizationIssue::WrongAuthority {
+            expected: expected.authority,
+            actual: authority,
+        });
+    }
+
+    AuthorizationReport {
+        authority,
+        issues,
+        warnings,
+    }
+}
+
+/// Check an authorization against the authority's current nonce and code
+pub fn check_authorization_state(
+    report: &mut AuthorizationReport,
+    authorization: &SignedAuthorization,
+    expected_nonce: u64,
+    code: &Bytes,
+) {
+    if authorization.nonce != expected_nonce {
+        report.issues.push(AuthorizationIssue::WrongNonce {
+            expected: expected_nonce,
+            actual: authorization.nonce,
+        });
+    }
+    if !code.is_empty() && parse_delegation_designator(code).is_none() {
+        report.issues.push(AuthorizationIssue::AuthorityHasCode);
+    }
+}
+
+impl<P: Provider> Eip7702Builder<P> {
+    /// Verify a signed authorization against expectations and the current chain state
+    pub async fn verify_authorization(
+        &self,
+        authorization: &SignedAuthorization,
+        expected: &ExpectedAuthorization,
+    ) -> Result<AuthorizationReport> {
+        let mut report = inspect_authorization(authorization, expected);
+
+        // Chain state is read for the authority that actually signed, if it can be recovered
+        let authority = report.authority.unwrap_or(expected.authority);
+        let expected_nonce = self.authorization_nonce(authority, expected.sender).await?;
+        let code = self.provider().get_code_at(authority).await?;
+        check_authorization_state(&mut report, authorization, expected_nonce, &code);
+
+        Ok(report)
+    }
+
+    /// Verify a signed authorization and fail with [`Error::InvalidAuthorization`] if it has any issue
+    pub async fn ensure_valid_authorization(
+        &self,
+        authorization: &SignedAuthorization,
+        expected: &ExpectedAuthorization,
+    ) -> Result<Address> {
+        let report = self.verify_authorization(authorization, expected).await?;
+        match report.authority {
+            Some(authority) if report.is_valid() => Ok(authority),
+            _ => Err(Error::InvalidAuthorization(Box::new(report))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        eips::eip7702::Authorization,
+        primitives::address,
+        signers::{SignerSync, local::PrivateKeySigner},
+    };
+
+    use super::*;
+
+    const DELEGATE: Address = address!("0x80296FF8D1ED46f8e3C7992664D13B833504c2Bb");
+
+    fn signed(signer: &PrivateKeySigner, chain_id: u64) -> SignedAuthorization {
+        let authorization = Authorization {
+            chain_id: U256::from(chain_id),
+            address: DELEGATE,
+            nonce: 0,
+        };
+        let signature = signer.sign_hash_sync(&authorization.signature_hash()).unwrap();
+        authorization.into_signed(signature)
+    }
+
+    fn expected(signer: &PrivateKeySigner, allow_any_chain: bool) -> ExpectedAuthorization {
+        ExpectedAuthorization {
+            authority: signer.address(),
+            delegate: DELEGATE,
+            chain_id: 1,
+            sender: Address::ZERO,
+            allow_any_chain,
+        }
+    }
+
+    #[test]
+    fn accepts_authorization_for_the_chain() {
+        let signer = PrivateKeySigner::random();
+        let report = inspect_authorization(&signed(&signer, 1), &expected(&signer, false));
+        assert!(report.is_valid());
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.authority, Some(signer.address()));
+    }
+
+    #[test]
+    fn rejects_chain_agnostic_authorization_by_default() {
+        let signer = PrivateKeySigner::random();
+        let report = inspect_authorization(&signed(&signer, 0), &expected(&signer, false));
+        assert_eq!(report.issues, vec![AuthorizationIssue::ChainAgnostic]);
+    }
+
+    #[test]
+    fn warns_about_chain_agnostic_authorization_when_any_chain_is_allowed() {
+        let signer = PrivateKeySigner::random();
+        let authorization = signed(&signer, 0);
+        let report = inspect_authorization(&authorization, &expected(&signer, true));
+        assert!(report.is_valid());
+        assert_eq!(report.warnings, vec![AuthorizationIssue::ChainAgnostic]);
+        assert!(chain_agnostic_warning(&authorization).is_some());
+    }
+
+    #[test]
+    fn allowing_any_chain_still_rejects_other_chains() {
+        let signer = PrivateKeySigner::random();
+        let report = inspect_authorization(&signed(&signer, 5), &expected(&signer, true));
+        assert_eq!(
+            report.issues,
+            vec![AuthorizationIssue::WrongChain {
+                expected: 1,
+                actual: U256::from(5)
+            }]
+        );
+        assert_eq!(chain_agnostic_warning(&signed(&signer, 5)), None);
+    }
+}
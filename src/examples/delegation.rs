@@ -0,0 +1,33 @@
+use alloy::primitives::Address;
+use eyre::Result;
+
+use crate::core::{types::TransactionPattern, Config, Eip7702Builder};
+
+/// Re-points an EOA at a new delegate, or revokes its delegation when `delegate` is the zero address
+pub async fn redelegate(config: Config, delegate: Address, pattern: TransactionPattern) -> Result<()> {
+    let builder = Eip7702Builder::new(config.clone());
+
+    // Bob manages his own EOA, or relays the change for Alice
+    let (authority, sender) = match pattern {
+        TransactionPattern::SelfAuthorization => (config.bob_signer.clone(), config.bob_signer.clone()),
+        TransactionPattern::RelayerPattern => (config.alice_signer.clone(), config.bob_signer.clone()),
+    };
+
+    println!("Delegation before: {:?}", builder.get_delegation(authority.address()).await?);
+
+    let receipt = if delegate.is_zero() {
+        builder.revoke_delegation(&authority, &sender).await?
+    } else {
+        builder.redelegate(&authority, delegate, &sender).await?
+    };
+    println!("Transaction included in block: {}", receipt.block_number.expect("Failed to get block number"));
+
+    println!("Delegation after: {:?}", builder.get_delegation(authority.address()).await?);
+
+    Ok(())
+}
+
+/// Revokes the delegation of an EOA
+pub async fn revoke_delegation(config: Config, pattern: TransactionPattern) -> Result<()> {
+    redelegate(config, Address::ZERO, pattern).await
+}
@@ -1,12 +1,12 @@
-use crate::core::{Config, Eip7702Builder};
+use crate::core::{Config, Eip7702Builder, WalletSigner};
 use eyre::Result;
 
-pub async fn initialize_wallet(config: Config, sender: alloy::signers::local::PrivateKeySigner) -> Result<()> {
+pub async fn initialize_wallet(config: Config, sender: WalletSigner) -> Result<()> {
     let builder = Eip7702Builder::new(config.clone());
    
     // help alice create a wallet_core and initialize it(create storage)
-    let signed_authorization = builder.create_authorization(&config.alice_signer).await?;
-    let tx = builder.build_initialize_transaction(&sender, config.alice_signer.address(), signed_authorization);
+    let signed_authorization = builder.create_authorization(&config.alice_signer, sender.address()).await?;
+    let tx = builder.build_initialize_transaction(config.alice_signer.address(), signed_authorization)?;
 
     let receipt = builder.send_transaction(tx, &sender).await?;
     println!("Transaction included in block: {}", receipt.block_number.expect("Failed to get block number"));
@@ -16,3 +16,23 @@ pub async fn initialize_wallet(config: Config, sender: alloy::signers::local::Pr
 
     Ok(())
 }
+
+// delegate and initialize alice and bob in a single transaction paid by sender
+pub async fn initialize_wallets(config: Config, sender: WalletSigner) -> Result<()> {
+    let builder = Eip7702Builder::new(config.clone());
+
+    let authorizers = vec![config.alice_signer.clone(), config.bob_signer.clone()];
+    let (receipt, outcomes) = builder.initialize_batch(&authorizers, &sender).await?;
+    println!("Transaction included in block: {}", receipt.block_number.expect("Failed to get block number"));
+
+    for outcome in outcomes {
+        match outcome.storage_address {
+            Some(storage_address) => {
+                println!("{} {:?}, storage address: {}", outcome.authority, outcome.status, storage_address)
+            }
+            None => println!("{} {:?}", outcome.authority, outcome.status),
+        }
+    }
+
+    Ok(())
+}
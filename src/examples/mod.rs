@@ -2,9 +2,11 @@ pub mod patterns;
 pub mod simplest;
 pub mod normal;
 pub mod initialize;
+pub mod delegation;
 
 // Re-export main example functions for convenience
 pub use patterns::{self_authorization_transaction, relayer_transaction, demonstrate_patterns};
 pub use simplest::make_transaction as simple_transaction;
 pub use normal::transfer_erc20;
-pub use initialize::initialize_wallet; 
\ No newline at end of file
+pub use initialize::{initialize_wallet, initialize_wallets};
+pub use delegation::{redelegate, revoke_delegation}; 
\ No newline at end of file
@@ -1,5 +1,5 @@
 use alloy::{
-    network::TransactionBuilder,
+    network::{EthereumWallet, TransactionBuilder},
     primitives::{U256, address},
     providers::{Provider, ProviderBuilder},
     rpc::types::TransactionRequest,
@@ -12,7 +12,7 @@ use crate::core::{contracts::ERC20, Config};
 pub async fn transfer_erc20(config: Config) -> Result<()> {
     let sender = config.bob_signer.clone();
     let provider = ProviderBuilder::new()
-        .wallet(sender.clone())
+        .wallet(EthereumWallet::from(sender.clone()))
         .on_http(config.rpc_url.clone());
 
     // TODO：change to the token address
@@ -12,7 +12,7 @@ pub async fn self_authorization_transaction(config: Config) -> Result<()> {
     builder.print_balances("Initial Balances").await?;
 
     let authorization = builder
-        .create_authorization(&config.bob_signer)
+        .create_authorization(&config.bob_signer, sender.address())
         .await?;
     let nonce = builder
         .get_wallet_nonce(config.bob_signer.address())
@@ -20,7 +20,6 @@ pub async fn self_authorization_transaction(config: Config) -> Result<()> {
 
     let transfer_amount = U256::from(1e17); // 0.1 token
     let calls = vec![builder.create_token_transfer_call(
-        &sender,
         config.receiver_address,
         transfer_amount,
     )];
@@ -28,15 +27,16 @@ pub async fn self_authorization_transaction(config: Config) -> Result<()> {
     let validation_hash = builder
         .get_validation_hash(config.bob_signer.address(), nonce, &calls)
         .await?;
-    let validation_data = builder.sign_validation_data(&config.bob_signer, &validation_hash);
+    let validation_data = builder
+        .sign_validation_data(&config.bob_signer, config.bob_signer.address(), nonce, &calls, &validation_hash)
+        .await?;
 
     let tx = builder.build_execute_with_validator_transaction(
-        &sender,
         config.bob_signer.address(),
         authorization,
         &calls,
         validation_data,
-    );
+    ).await?;
 
     let receipt = builder.send_transaction(tx, &config.bob_signer).await?;
     println!("✅ Transaction successful! Gas used: {}", receipt.gas_used);
@@ -60,7 +60,7 @@ pub async fn relayer_transaction(config: Config) -> Result<()> {
     builder.print_balances("Initial Balances").await?;
 
     let authorization = builder
-        .create_authorization(&config.alice_signer)
+        .create_authorization(&config.alice_signer, sender.address())
         .await?;
     
     let nonce = builder
@@ -69,7 +69,6 @@ pub async fn relayer_transaction(config: Config) -> Result<()> {
 
     let transfer_amount = U256::from(500000); // 0.1 token
     let calls = vec![builder.create_token_transfer_call(
-        &config.alice_signer,
         config.receiver_address,
         transfer_amount,
     )];
@@ -77,15 +76,16 @@ pub async fn relayer_transaction(config: Config) -> Result<()> {
     let validation_hash = builder
         .get_validation_hash(config.alice_signer.address(), nonce, &calls)
         .await?;
-    let validation_data = builder.sign_validation_data(&config.alice_signer, &validation_hash);
+    let validation_data = builder
+        .sign_validation_data(&config.alice_signer, config.alice_signer.address(), nonce, &calls, &validation_hash)
+        .await?;
 
     let tx = builder.build_execute_with_validator_transaction(
-        &sender,
         config.alice_signer.address(), // Transaction goes to Alice's address
         authorization,
         &calls,
         validation_data,
-    );
+    ).await?;
 
     // Bob sends the transaction and pays the gas
     let receipt = builder.send_transaction(tx, &config.bob_signer).await?;
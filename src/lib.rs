@@ -1,5 +1,4 @@
 pub mod core;
 pub mod examples;
 
-pub use core::{Config, Eip7702Builder};
-pub use eyre::Result;
+pub use core::{Config, Eip7702Builder, Error, Result};
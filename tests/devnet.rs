@@ -0,0 +1,121 @@
+use alloy::primitives::{Address, U256};
+use tx_7702::{
+    core::{Delegation, Devnet, devnet::INITIAL_TOKEN_BALANCE},
+    examples, Eip7702Builder,
+};
+
+const NEEDS_DEVNET: &str = "needs anvil on the PATH and the bytecode in contracts/bytecode";
+
+/// Start a devnet; a missing `anvil` or contract bytecode fails the test
+async fn devnet() -> Devnet {
+    Devnet::spawn()
+        .await
+        .unwrap_or_else(|e| panic!("devnet does not start ({}): {}", NEEDS_DEVNET, e))
+}
+
+#[tokio::test]
+async fn spawn_funds_alice_and_bob() {
+    let devnet = devnet().await;
+    let config = devnet.config();
+    let builder = Eip7702Builder::new(config.clone());
+
+    for account in [config.alice_signer.address(), config.bob_signer.address()] {
+        assert_eq!(builder.get_token_balance(account).await.unwrap(), INITIAL_TOKEN_BALANCE);
+        assert_eq!(builder.get_delegation(account).await.unwrap(), Delegation::NotDelegated);
+    }
+}
+
+#[tokio::test]
+async fn initialize_wallet() {
+    let devnet = devnet().await;
+    let config = devnet.config().clone();
+    let alice = config.alice_signer.address();
+
+    examples::initialize_wallet(config.clone(), devnet.deployer().clone().into())
+        .await
+        .unwrap();
+
+    let builder = Eip7702Builder::new(config.clone());
+    assert_eq!(
+        builder.get_delegation(alice).await.unwrap(),
+        Delegation::WalletCore(config.wallet_core_address)
+    );
+    assert_ne!(builder.get_storage_address(alice).await.unwrap(), Address::ZERO);
+}
+
+#[tokio::test]
+async fn initialize_wallets() {
+    let devnet = devnet().await;
+    let config = devnet.config().clone();
+
+    examples::initialize_wallets(config.clone(), devnet.deployer().clone().into())
+        .await
+        .unwrap();
+
+    let builder = Eip7702Builder::new(config.clone());
+    for account in [config.alice_signer.address(), config.bob_signer.address()] {
+        assert_ne!(builder.get_storage_address(account).await.unwrap(), Address::ZERO);
+    }
+}
+
+#[tokio::test]
+async fn self_authorization_transaction() {
+    let devnet = devnet().await;
+    devnet.initialize_wallets().await.unwrap();
+    let config = devnet.config().clone();
+
+    examples::self_authorization_transaction(config.clone()).await.unwrap();
+
+    let builder = Eip7702Builder::new(config.clone());
+    let bob = builder.get_token_balance(config.bob_signer.address()).await.unwrap();
+    let receiver = builder.get_token_balance(config.receiver_address).await.unwrap();
+    assert_eq!(receiver, U256::from(1e17));
+    assert_eq!(bob, INITIAL_TOKEN_BALANCE - receiver);
+}
+
+#[tokio::test]
+async fn relayer_transaction() {
+    let devnet = devnet().await;
+    devnet.initialize_wallets().await.unwrap();
+    let config = devnet.config().clone();
+
+    examples::relayer_transaction(config.clone()).await.unwrap();
+
+    let builder = Eip7702Builder::new(config.clone());
+    let alice = builder.get_token_balance(config.alice_signer.address()).await.unwrap();
+    let receiver = builder.get_token_balance(config.receiver_address).await.unwrap();
+    assert_eq!(receiver, U256::from(500000));
+    assert_eq!(alice, INITIAL_TOKEN_BALANCE - receiver);
+}
+
+#[tokio::test]
+async fn demonstrate_patterns() {
+    let devnet = devnet().await;
+    devnet.initialize_wallets().await.unwrap();
+    let config = devnet.config().clone();
+
+    examples::demonstrate_patterns(config.clone()).await.unwrap();
+
+    let builder = Eip7702Builder::new(config.clone());
+    let receiver = builder.get_token_balance(config.receiver_address).await.unwrap();
+    assert_eq!(receiver, U256::from(1e17) + U256::from(500000));
+}
+
+#[tokio::test]
+async fn compute_validation_hash_matches_wallet_core() {
+    let devnet = devnet().await;
+    devnet.initialize_wallets().await.unwrap();
+    let config = devnet.config().clone();
+    let alice = config.alice_signer.address();
+
+    let builder = Eip7702Builder::new(config.clone());
+    let calls = vec![
+        builder.create_token_transfer_call(config.receiver_address, U256::from(500000)),
+        builder.create_token_transfer_call(config.bob_signer.address(), U256::from(1)),
+    ];
+    let nonce = builder.get_wallet_nonce(alice).await.unwrap();
+
+    let computed = builder.compute_validation_hash(devnet.anvil().chain_id(), alice, nonce, &calls);
+    let on_chain = builder.get_validation_hash(alice, nonce, &calls).await.unwrap();
+    assert_eq!(computed, on_chain);
+}